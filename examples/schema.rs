@@ -0,0 +1,64 @@
+use std::env::current_dir;
+use std::fs::create_dir_all;
+
+use cosmwasm_schema::{export_schema, export_schema_with_title, remove_schemas, schema_for};
+
+use my_contract::msg::{
+    AllowlistStatusResponse, BreedFinishedCountResponse, BreedInfoResponse,
+    BreedRequestsCountResponse, BreedStatsResponse, BreededCountResponse, BreedingHookExecuteMsg,
+    CheckRoyaltiesResponse, ChildInfoResponse, ClaimableBreedsResponse, ConfigResponse, CurrentPriceResponse, HandleMsg, HooksResponse,
+    InitMsg, LeaderboardResponse, MigrateMsg, PairingsResponse, ParentCollectionsResponse, PayoutTableResponse,
+    PreviewChildUriResponse, RoyaltiesInfoResponse,
+    QueryBreedingsLengthResponse, QueryBreedingsResponse, QueryMsg,
+    QueryUserBreedingsLengthResponse, QueryUserBreedingsResponse, ReceiptMsg, ReceiveMsg, SudoMsg,
+    TreasuryResponse,
+};
+use my_contract::state::{Breed, ConfigState};
+
+fn main() {
+    let mut out_dir = current_dir().unwrap();
+    out_dir.push("schema");
+    create_dir_all(&out_dir).unwrap();
+    remove_schemas(&out_dir).unwrap();
+
+    // Messages
+    export_schema(&schema_for!(InitMsg), &out_dir);
+    export_schema(&schema_for!(HandleMsg), &out_dir);
+    export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema(&schema_for!(MigrateMsg), &out_dir);
+    export_schema(&schema_for!(SudoMsg), &out_dir);
+    export_schema(&schema_for!(ReceiveMsg), &out_dir);
+    export_schema(&schema_for!(ReceiptMsg), &out_dir);
+    export_schema(&schema_for!(BreedingHookExecuteMsg), &out_dir);
+
+    // State
+    export_schema(&schema_for!(ConfigState), &out_dir);
+    export_schema(&schema_for!(Breed), &out_dir);
+
+    // Query responses
+    export_schema(&schema_for!(ConfigResponse), &out_dir);
+    export_schema(&schema_for!(BreedInfoResponse), &out_dir);
+    export_schema(&schema_for!(QueryBreedingsResponse), &out_dir);
+    export_schema(&schema_for!(BreedStatsResponse), &out_dir);
+    export_schema(&schema_for!(PayoutTableResponse), &out_dir);
+    export_schema(&schema_for!(TreasuryResponse), &out_dir);
+    export_schema(&schema_for!(CurrentPriceResponse), &out_dir);
+    export_schema(&schema_for!(AllowlistStatusResponse), &out_dir);
+    export_schema(&schema_for!(ParentCollectionsResponse), &out_dir);
+    export_schema(&schema_for!(PairingsResponse), &out_dir);
+    export_schema(&schema_for!(ClaimableBreedsResponse), &out_dir);
+    export_schema(&schema_for!(HooksResponse), &out_dir);
+    export_schema(&schema_for!(LeaderboardResponse), &out_dir);
+    export_schema(&schema_for!(PreviewChildUriResponse), &out_dir);
+    export_schema(&schema_for!(ChildInfoResponse), &out_dir);
+    export_schema(&schema_for!(RoyaltiesInfoResponse), &out_dir);
+    export_schema(&schema_for!(CheckRoyaltiesResponse), &out_dir);
+
+    // Responses that are plain values or lists need an explicit title
+    export_schema_with_title(&mut schema_for!(BreededCountResponse), &out_dir, "BreededCountResponse");
+    export_schema_with_title(&mut schema_for!(BreedRequestsCountResponse), &out_dir, "BreedRequestsCountResponse");
+    export_schema_with_title(&mut schema_for!(BreedFinishedCountResponse), &out_dir, "BreedFinishedCountResponse");
+    export_schema_with_title(&mut schema_for!(QueryBreedingsLengthResponse), &out_dir, "QueryBreedingsLengthResponse");
+    export_schema_with_title(&mut schema_for!(QueryUserBreedingsResponse), &out_dir, "QueryUserBreedingsResponse");
+    export_schema_with_title(&mut schema_for!(QueryUserBreedingsLengthResponse), &out_dir, "QueryUserBreedingsLengthResponse");
+}
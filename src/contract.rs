@@ -1,73 +1,318 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    Addr, Api, Binary, CanonicalAddr, Coin, CosmosMsg, Env, Extern, HandleResponse,
-    HumanAddr, InitResponse, Querier, StdError, StdResult, Storage, Uint128, WasmMsg,
+    attr, from_binary, to_binary, Addr, Attribute, BankMsg, Binary, CanonicalAddr, Coin,
+    CosmosMsg, Deps, DepsMut, Env, Response, MessageInfo, Order,
+    Reply, ReplyOn, StdResult, Storage, SubMsg, Uint128, WasmMsg,
 };
-use cosmwasm_storage::{singleton, singleton_read, ReadonlySingleton, Singleton};
-use std::cmp;
-use cw721::{MinterResponse, TokenInfoResponse, ContractInfoResponse, OwnerOfResponse};
+use cosmwasm_storage::{bucket, bucket_read, singleton, singleton_read, Bucket, ReadonlyBucket};
+use cw721::{Cw721ExecuteMsg, Cw721QueryMsg, Cw721ReceiveMsg, OwnerOfResponse};
 use cw721_base::msg::InstantiateMsg as NftInstantiateMsg;
-use cw721_base::msg::ExecuteMsg as NftExecuteMsg;
-use cw721_base::msg::QueryMsg as NftQueryMsg;
+use cw721_base::msg::{ExecuteMsg as NftExecuteMsg, MintMsg};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use prost::Message;
+use sha2::{Digest, Sha256};
+use std::cmp::Reverse;
+
+use crate::error::ContractError;
+use crate::events;
+use crate::msg::{
+    AllowlistStatusResponse, BreedBatchResponse, BreedFinishedCountResponse, BreedInfoResponse,
+    BreedRequestsCountResponse, BreedStatsResponse, BreededCountResponse, BreedingHookMsg,
+    CheckRoyaltiesResponse, ChildCollectionMsg, ChildInfoResponse, ClaimableBreed, LeaderboardEntryResponse, LeaderboardKindMsg, LeaderboardResponse,
+    ClaimableBreedsResponse, ConfigResponse,
+    CurrentPriceResponse, FeeDenomMsg, HandleMsg, HookResponse, HooksResponse, InitMsg, Metadata, MigrateMsg, PairingResponse,
+    PairingsResponse, ParentCollectionsResponse, ParentNft, PayoutShareMsg, PayoutTableResponse,
+    PreviewChildUriResponse, RoyaltiesInfoResponse, RoyaltyMsg,
+    QueryBreedingsLengthResponse, QueryBreedingsResponse, QueryMsg,
+    QueryUserBreedingsLengthResponse, QueryUserBreedingsResponse, ReceiptMsg, ReceiveMsg, SudoMsg,
+    Trait, TreasuryEntry, TreasuryResponse, WithdrawAllResponse,
+};
+use crate::state::{
+    AllowlistRound, Breed, BreedCount, BreedOffer, BreedStats, ChildCollectionTarget, ChildInfo, ChildRoyalty, ChildTokenIdScheme, Hook, HookEvent, LeaderboardEntry, LeaderboardKind, Config, ConfigState, FeeDenom,
+    FeeLedger, Pairing, ParentCollection, PayoutShare, PayoutTable, PricingMode, RoyaltyConfig,
+    DEFAULT_MAX_BATCH_SIZE,
+};
 
 // Constants
 const CONFIG_KEY: &[u8] = b"config";
 const BREED_COUNT_KEY: &[u8] = b"breed_count";
+const TOTAL_STATS_KEY: &[u8] = b"total_stats";
+const ADDRESS_STATS_KEY: &[u8] = b"address_stats";
 const BREEDS_KEY: &[u8] = b"breeds";
+const OWNER_BREEDS_KEY: &[u8] = b"owner_breeds";
+const AUTO_CLAIM_BREEDS_KEY: &[u8] = b"auto_claim_breeds";
+const RESCUED_BREEDS_KEY: &[u8] = b"rescued_breeds";
+const PAYOUT_TABLE_KEY: &[u8] = b"payout_table";
+const FEE_LEDGER_KEY: &[u8] = b"fee_ledger";
+const ALLOWLIST_KEY: &[u8] = b"allowlist";
+const ALLOWLIST_USAGE_KEY: &[u8] = b"allowlist_usage";
+const PARENT_COLLECTIONS_KEY: &[u8] = b"parent_collections";
+const PAIRINGS_KEY: &[u8] = b"pairings";
+const BREEDED_COUNT_KEY: &[u8] = b"breeded_count";
+const PENDING_CHILD_COLLECTION_KEY: &[u8] = b"pending_child_collection";
+const BREED_OFFERS_KEY: &[u8] = b"breed_offers";
+const HOOKS_KEY: &[u8] = b"hooks";
+const LEADERBOARD_SCORES_KEY: &[u8] = b"leaderboard_scores";
+const LEADERBOARD_RANKS_KEY: &[u8] = b"leaderboard_ranks";
+const CHILD_INFOS_KEY: &[u8] = b"child_infos";
+const CHILD_ROYALTIES_KEY: &[u8] = b"child_royalties";
+
+// Reply ids of the submessages this contract dispatches
+const INSTANTIATE_CHILD_REPLY_ID: u64 = 1;
+const HOOK_REPLY_ID: u64 = 2;
+
+// Hooks run with a bounded gas budget and their failures are swallowed in the reply, so a
+// misbehaving hook contract cannot block breeds or withdrawals
+const HOOK_GAS_LIMIT: u64 = 300_000;
+const MAX_HOOKS: u32 = 10;
+
+// Page sizes of list queries
+const DEFAULT_QUERY_LIMIT: u32 = 10;
+const MAX_QUERY_LIMIT: u32 = 30;
+
+// Payout shares are expressed in basis points and must add up to this total
+const BPS_TOTAL: u16 = 10_000;
+
+// Fixed point precision of the exponential pricing growth factor
+const PRICE_PRECISION: u128 = 1_000_000_000;
+
+// Placeholders of the child uri template, a u64 has at most 20 digits to pad
+const CHILD_URI_BASE: &str = "{base}";
+const CHILD_URI_ID: &str = "{id}";
+const MAX_PADDED_WIDTH: u8 = 20;
+
+// Length limits of child metadata, in bytes
+const MAX_METADATA_NAME_LENGTH: usize = 128;
+const MAX_METADATA_DESCRIPTION_LENGTH: usize = 1_024;
+const MAX_METADATA_URL_LENGTH: usize = 512;
+const MAX_METADATA_TRAITS: usize = 32;
+const MAX_TRAIT_TYPE_LENGTH: usize = 64;
+const MAX_TRAIT_VALUE_LENGTH: usize = 256;
+
+// CW-2981 royalties are whole percentages of the sale price
+const MAX_ROYALTY_PERCENTAGE: u64 = 100;
+
+// Messages, hook submessages and attributes collected while starting or settling breeds
+type BreedMessages = (Vec<CosmosMsg>, Vec<SubMsg>, Vec<Attribute>);
 
 // Initialization function
-pub fn init(
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    config: Config,
-) -> StdResult<InitResponse> {
+    msg: InitMsg,
+) -> Result<Response, ContractError> {
+    let pricing = msg.pricing.unwrap_or_default();
+    validate_pricing(&pricing)?;
+    let child_token_id_scheme = msg.child_token_id_scheme.unwrap_or_default();
+    validate_child_naming(&child_token_id_scheme, &msg.child_base_uri, &msg.child_uri_template)?;
+    let royalty = match msg.royalty {
+        Some(royalty) => royalty_config(deps.as_ref(), royalty)?,
+        None => None,
+    };
+
+    let breed_price_cw20 = match msg.breed_price_cw20 {
+        Some(token) => Some(deps.api.addr_canonicalize(&token)?),
+        None => None,
+    };
+    let config = Config {
+        breed_count_limit: msg.breed_count_limit,
+        breed_duration: msg.breed_duration,
+        breed_price_amount: msg.breed_price_amount,
+        breed_price_denom: msg.breed_price_denom,
+        breed_price_cw20,
+        pricing,
+        child_contract_addr: None,
+        paused: false,
+        max_batch_size: msg.max_batch_size.unwrap_or(DEFAULT_MAX_BATCH_SIZE),
+        receipt_contract_addr: None,
+        child_token_id_scheme,
+        child_base_uri: msg.child_base_uri,
+        child_uri_template: msg.child_uri_template,
+        royalty,
+    };
+
     let config_state = ConfigState {
-        config: Some(config.clone()),
-        owner: deps.api.canonical_address(&info.sender)?,
+        config,
+        owner: deps.api.addr_canonicalize(info.sender.as_str())?,
     };
     save_config(deps.storage, &config_state)?;
 
     let breed_count = BreedCount {
         count: 0,
         latest_id: 0,
+        minted: 0,
+        direct_minted: 0,
     };
     save_breed_count(deps.storage, &breed_count)?;
 
-    Ok(InitResponse::default())
+    // All revenue goes to the instantiator until the owner configures a payout table
+    let payout_table = PayoutTable {
+        shares: vec![PayoutShare {
+            recipient: config_state.owner.clone(),
+            share_bps: BPS_TOTAL,
+        }],
+        dust_recipient: config_state.owner.clone(),
+    };
+    save_payout_table(deps.storage, &payout_table)?;
+
+    let mut submessages = vec![];
+    if let Some(collection) = msg.child_collection {
+        submessages.push(instantiate_child_collection_msg(deps, &env, ChildCollectionTarget::Default, collection)?);
+    }
+
+    Ok(Response {
+        submessages,
+        ..Response::default()
+    })
 }
 
 // Handle messages function
-pub fn handle(
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     msg: HandleMsg,
-) -> StdResult<HandleResponse> {
+) -> Result<Response, ContractError> {
     match msg {
-        HandleMsg::UpdateConfig { breed_count_limit, breed_duration, breed_price_amount, breed_price_denom, owner } => update_config(deps, env, info, breed_count_limit, breed_duration, breed_price_amount, breed_price_denom, owner),
-        HandleMsg::StartBreed {} => start_breed(deps, env, info),
-        HandleMsg::Breed { nft_token_id1, nft_token_id2 } => breed(deps, env, info, nft_token_id1, nft_token_id2),
-        HandleMsg::Mint { extension, token_id, token_uri } => mint(deps, env, info, extension, token_id, token_uri),
+        HandleMsg::UpdateConfig { breed_count_limit, breed_duration, breed_price_amount, breed_price_denom, breed_price_cw20, pricing, max_batch_size, child_token_id_scheme, child_base_uri, child_uri_template, royalty, owner } => update_config(deps, info, breed_count_limit, breed_duration, breed_price_amount, breed_price_denom, breed_price_cw20, pricing, max_batch_size, child_token_id_scheme, child_base_uri, child_uri_template, royalty, owner),
+        HandleMsg::Breed { parent1, parent2, proof, auto_claim_tip } => breed(deps, env, info, parent1, parent2, proof, auto_claim_tip),
+        HandleMsg::BreedBatch { collection1, collection2, pairs, auto_claim_tip } => breed_batch(deps, env, info, collection1, collection2, pairs, auto_claim_tip),
+        HandleMsg::Mint { extension } => mint(deps, env, info, extension),
+        HandleMsg::PayRoyalty { token_id } => pay_royalty(deps, info, token_id),
+        HandleMsg::Receive(wrapper) => receive_cw20(deps, env, info, wrapper),
+        HandleMsg::ReceiveNft(wrapper) => receive_nft(deps, env, info, wrapper),
+        HandleMsg::CancelBreed { breed_id } => cancel_breed(deps, env, info, breed_id),
         HandleMsg::Withdraw { breed_id } => withdraw(deps, env, info, breed_id),
-        HandleMsg::WithdrawFund {} => withdraw_fund(deps, env, info),
+        HandleMsg::WithdrawAll { start_after, limit } => withdraw_all(deps, env, info, start_after, limit),
+        HandleMsg::ClaimFor { breed_id } => claim_for(deps, env, info, breed_id),
+        HandleMsg::TransferBreed { breed_id, recipient } => transfer_breed(deps, env, info, breed_id, recipient),
+        HandleMsg::OfferBreed { breed_id, recipient, price } => offer_breed(deps, env, info, breed_id, recipient, price),
+        HandleMsg::AcceptBreed { breed_id } => accept_breed(deps, env, info, breed_id),
+        HandleMsg::WithdrawFund { denom, amount, recipient } => withdraw_fund(deps, info, denom, amount, recipient),
+        HandleMsg::UpdatePayoutTable { shares, dust_recipient } => update_payout_table(deps, info, shares, dust_recipient),
+        HandleMsg::SetAllowlist { merkle_root, discount_bps, quota } => set_allowlist(deps, info, merkle_root, discount_bps, quota),
+        HandleMsg::CloseAllowlist {} => close_allowlist(deps, info),
+        HandleMsg::AddParentCollection { addr } => add_parent_collection(deps, info, addr),
+        HandleMsg::RemoveParentCollection { addr } => remove_parent_collection(deps, info, addr),
+        HandleMsg::SetPairing { collection1, collection2, breed_price_amount, breed_duration, child_contract_addr } => set_pairing(deps, info, collection1, collection2, breed_price_amount, breed_duration, child_contract_addr),
+        HandleMsg::RemovePairing { collection1, collection2 } => remove_pairing(deps, info, collection1, collection2),
+        HandleMsg::InstantiateChildCollection { collection, pairing } => instantiate_child_collection(deps, env, info, collection, pairing),
+        HandleMsg::InstantiateReceiptCollection { collection } => instantiate_receipt_collection(deps, env, info, collection),
+        HandleMsg::AddHook { addr, events } => add_hook(deps, info, addr, events),
+        HandleMsg::RemoveHook { addr } => remove_hook(deps, info, addr),
+    }
+}
+
+// Migrate function
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    Ok(Response::default())
+}
+
+// Sudo function, only callable by chain governance
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+    let mut response = match msg {
+        SudoMsg::ForcePause { paused } => sudo_force_pause(deps, paused),
+        SudoMsg::ReplaceOwner { owner } => sudo_replace_owner(deps, owner),
+        SudoMsg::UpdateConfig { breed_count_limit, breed_duration, breed_price_amount, breed_price_denom, breed_price_cw20, pricing, max_batch_size, child_token_id_scheme, child_base_uri, child_uri_template, royalty, child_contract_addr, receipt_contract_addr, paused } => sudo_update_config(deps, breed_count_limit, breed_duration, breed_price_amount, breed_price_denom, breed_price_cw20, pricing, max_batch_size, child_token_id_scheme, child_base_uri, child_uri_template, royalty, child_contract_addr, receipt_contract_addr, paused),
+        SudoMsg::RescueNfts { breed_id, recipient } => sudo_rescue_nfts(deps, env, breed_id, recipient),
+    }?;
+    // Every governance action is marked right after its single action attribute
+    response.attributes.insert(1, attr(events::GOVERNANCE, true));
+    Ok(response)
+}
+
+// Reply function
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        INSTANTIATE_CHILD_REPLY_ID => reply_instantiate_child_collection(deps, msg),
+        HOOK_REPLY_ID => reply_hook_failed(msg),
+        id => Err(ContractError::UnknownReplyId { id }),
     }
 }
 
 // Query function
-pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     match msg {
-        QueryMsg::Config {} => to_binary(&query_config(deps)?),
-        QueryMsg::BreedInfo { breed_id } => to_binary(&query_breed_info(deps, breed_id)?),
-        QueryMsg::BreededCount { parent_nft_token_id } => to_binary(&query_breeded_count(deps, parent_nft_token_id)?),
-        QueryMsg::BreedRequestsCount {} => to_binary(&query_breed_requests_count(deps)?),
-        QueryMsg::BreedFinishedCount {} => to_binary(&query_breed_finished_count(deps)?),
-        QueryMsg::QueryBreedings { count, from, sort } => to_binary(&query_breedings(deps, count, from, sort)?),
-        QueryMsg::QueryBreedingsLength {} => to_binary(&query_breedings_length(deps)?),
-        QueryMsg::QueryUserBreedings { count, from, sort, user } => to_binary(&query_user_breedings(deps, count, from, sort, user)?),
-        QueryMsg::QueryUserBreedingsLength { user } => to_binary(&query_user_breedings_length(deps, user)?),
+        QueryMsg::Config {} => Ok(to_binary(&query_config(deps)?)?),
+        QueryMsg::BreedInfo { breed_id } => Ok(to_binary(&query_breed_info(deps, breed_id)?)?),
+        QueryMsg::BreededCount { parent_nft_contract_addr, parent_nft_token_id } => Ok(to_binary(&query_breeded_count(deps, parent_nft_contract_addr, parent_nft_token_id)?)?),
+        QueryMsg::BreedRequestsCount {} => Ok(to_binary(&query_breed_requests_count(deps)?)?),
+        QueryMsg::BreedFinishedCount {} => Ok(to_binary(&query_breed_finished_count(deps)?)?),
+        QueryMsg::BreedStatsAtHeight { address, height } => Ok(to_binary(&query_breed_stats_at_height(deps, address, height)?)?),
+        QueryMsg::TotalStatsAtHeight { height } => Ok(to_binary(&query_total_stats_at_height(deps, height)?)?),
+        QueryMsg::QueryBreedings { count, from, sort } => Ok(to_binary(&query_breedings(deps, count, from, sort)?)?),
+        QueryMsg::QueryBreedingsLength {} => Ok(to_binary(&query_breedings_length(deps)?)?),
+        QueryMsg::QueryUserBreedings { count, from, sort, user } => Ok(to_binary(&query_user_breedings(deps, count, from, sort, user)?)?),
+        QueryMsg::QueryUserBreedingsLength { user } => Ok(to_binary(&query_user_breedings_length(deps, user)?)?),
+        QueryMsg::PayoutTable {} => Ok(to_binary(&query_payout_table(deps)?)?),
+        QueryMsg::Treasury {} => Ok(to_binary(&query_treasury(deps)?)?),
+        QueryMsg::CurrentPrice { collection1, collection2 } => Ok(to_binary(&query_current_price(deps, collection1, collection2)?)?),
+        QueryMsg::AllowlistStatus { address, proof } => Ok(to_binary(&query_allowlist_status(deps, address, proof)?)?),
+        QueryMsg::ParentCollections {} => Ok(to_binary(&query_parent_collections(deps)?)?),
+        QueryMsg::Pairings {} => Ok(to_binary(&query_pairings(deps)?)?),
+        QueryMsg::ClaimableBreeds { start_after, limit } => Ok(to_binary(&query_claimable_breeds(deps, env, start_after, limit)?)?),
+        QueryMsg::Hooks {} => Ok(to_binary(&query_hooks(deps)?)?),
+        QueryMsg::Leaderboard { kind, start_after, limit } => Ok(to_binary(&query_leaderboard(deps, kind, start_after, limit)?)?),
+        QueryMsg::PreviewChildUri { id, child_base_uri, child_uri_template } => Ok(to_binary(&query_preview_child_uri(deps, id, child_base_uri, child_uri_template)?)?),
+        QueryMsg::ChildInfo { token_id } => Ok(to_binary(&query_child_info(deps, token_id)?)?),
+        QueryMsg::RoyaltyInfo { token_id, sale_price } => Ok(to_binary(&query_royalty_info(deps, token_id, sale_price)?)?),
+        QueryMsg::CheckRoyalties {} => Ok(to_binary(&query_check_royalties(deps)?)?),
+        #[cfg(feature = "protobuf")]
+        QueryMsg::Protobuf { query } => query_protobuf(deps, env, *query),
     }
 }
 
+// Protobuf query function, every response is converted from the JSON one
+#[cfg(feature = "protobuf")]
+fn query_protobuf(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+    use crate::proto;
+
+    let encoded = match msg {
+        QueryMsg::Config {} => encode_protobuf(proto::ConfigResponse::from(query_config(deps)?)),
+        QueryMsg::BreedInfo { breed_id } => encode_protobuf(proto::BreedInfoResponse::from(query_breed_info(deps, breed_id)?)),
+        QueryMsg::BreededCount { parent_nft_contract_addr, parent_nft_token_id } => encode_protobuf(proto::CountResponse::from(query_breeded_count(deps, parent_nft_contract_addr, parent_nft_token_id)?)),
+        QueryMsg::BreedRequestsCount {} => encode_protobuf(proto::CountResponse::from(query_breed_requests_count(deps)?)),
+        QueryMsg::BreedFinishedCount {} => encode_protobuf(proto::CountResponse::from(query_breed_finished_count(deps)?)),
+        QueryMsg::BreedStatsAtHeight { address, height } => encode_protobuf(proto::BreedStatsResponse::from(query_breed_stats_at_height(deps, address, height)?)),
+        QueryMsg::TotalStatsAtHeight { height } => encode_protobuf(proto::BreedStatsResponse::from(query_total_stats_at_height(deps, height)?)),
+        QueryMsg::QueryBreedings { count, from, sort } => encode_protobuf(proto::QueryBreedingsResponse::from(query_breedings(deps, count, from, sort)?)),
+        QueryMsg::QueryBreedingsLength {} => encode_protobuf(proto::CountResponse::from(query_breedings_length(deps)?)),
+        QueryMsg::QueryUserBreedings { count, from, sort, user } => encode_protobuf(proto::QueryBreedingsResponse::from(query_user_breedings(deps, count, from, sort, user)?)),
+        QueryMsg::QueryUserBreedingsLength { user } => encode_protobuf(proto::CountResponse::from(query_user_breedings_length(deps, user)?)),
+        QueryMsg::PayoutTable {} => encode_protobuf(proto::PayoutTableResponse::from(query_payout_table(deps)?)),
+        QueryMsg::Treasury {} => encode_protobuf(proto::TreasuryResponse::from(query_treasury(deps)?)),
+        QueryMsg::CurrentPrice { collection1, collection2 } => encode_protobuf(proto::CurrentPriceResponse::from(query_current_price(deps, collection1, collection2)?)),
+        QueryMsg::AllowlistStatus { address, proof } => encode_protobuf(proto::AllowlistStatusResponse::from(query_allowlist_status(deps, address, proof)?)),
+        QueryMsg::ParentCollections {} => encode_protobuf(proto::ParentCollectionsResponse::from(query_parent_collections(deps)?)),
+        QueryMsg::Pairings {} => encode_protobuf(proto::PairingsResponse::from(query_pairings(deps)?)),
+        QueryMsg::ClaimableBreeds { start_after, limit } => encode_protobuf(proto::ClaimableBreedsResponse::from(query_claimable_breeds(deps, env, start_after, limit)?)),
+        QueryMsg::Hooks {} => encode_protobuf(proto::HooksResponse::from(query_hooks(deps)?)),
+        QueryMsg::Leaderboard { kind, start_after, limit } => encode_protobuf(proto::LeaderboardResponse::from(query_leaderboard(deps, kind, start_after, limit)?)),
+        QueryMsg::PreviewChildUri { id, child_base_uri, child_uri_template } => encode_protobuf(proto::PreviewChildUriResponse::from(query_preview_child_uri(deps, id, child_base_uri, child_uri_template)?)),
+        QueryMsg::ChildInfo { token_id } => encode_protobuf(proto::ChildInfoResponse::from(query_child_info(deps, token_id)?)),
+        QueryMsg::RoyaltyInfo { token_id, sale_price } => encode_protobuf(proto::RoyaltiesInfoResponse::from(query_royalty_info(deps, token_id, sale_price)?)),
+        QueryMsg::CheckRoyalties {} => encode_protobuf(proto::CheckRoyaltiesResponse::from(query_check_royalties(deps)?)),
+        QueryMsg::Protobuf { .. } => return Err(ContractError::NestedProtobufQuery {}),
+    };
+    Ok(encoded)
+}
+
+#[cfg(feature = "protobuf")]
+fn encode_protobuf<M: Message>(msg: M) -> Binary {
+    let mut buf = Vec::with_capacity(msg.encoded_len());
+    // Encoding only fails when the buffer runs out of capacity, which a Vec never does
+    msg.encode(&mut buf).unwrap();
+    Binary::from(buf)
+}
+
 // Function to save config state
 fn save_config(storage: &mut dyn Storage, config: &ConfigState) -> StdResult<()> {
     singleton(storage, CONFIG_KEY).save(config)
@@ -83,6 +328,44 @@ fn save_breed_count(storage: &mut dyn Storage, breed_count: &BreedCount) -> StdR
     singleton(storage, BREED_COUNT_KEY).save(breed_count)
 }
 
+// Function to save a stats checkpoint at `height`, of `address` or of the totals when it is None
+fn save_stats(storage: &mut dyn Storage, address: Option<&CanonicalAddr>, height: u64, stats: &BreedStats) -> StdResult<()> {
+    match address {
+        Some(address) => Bucket::multilevel(storage, &[ADDRESS_STATS_KEY, address.as_slice()]).save(&height.to_be_bytes(), stats),
+        None => bucket(storage, TOTAL_STATS_KEY).save(&height.to_be_bytes(), stats),
+    }
+}
+
+// Function to read the last stats checkpoint before `height`, or the live stats without a height
+fn read_stats(storage: &dyn Storage, address: Option<&CanonicalAddr>, height: Option<u64>) -> StdResult<BreedStats> {
+    let checkpoints: ReadonlyBucket<BreedStats> = match address {
+        Some(address) => ReadonlyBucket::multilevel(storage, &[ADDRESS_STATS_KEY, address.as_slice()]),
+        None => bucket_read(storage, TOTAL_STATS_KEY),
+    };
+    // The end bound is exclusive, so a height reads the counters as they were at the start of its
+    // block, before any checkpoint saved during it
+    let end = height.map(|height| height.to_be_bytes());
+    let latest = checkpoints.range(None, end.as_ref().map(|end| end.as_ref()), Order::Descending).next();
+    match latest {
+        Some(item) => Ok(item?.1),
+        None => Ok(BreedStats::default()),
+    }
+}
+
+// Checkpoints the live totals and adds to the counters of `address` at the current height
+fn record_stats(storage: &mut dyn Storage, env: &Env, breed_count: &BreedCount, address: &CanonicalAddr, breeds: u64, children: u64) -> StdResult<()> {
+    let totals = BreedStats {
+        breeds: breed_count.count,
+        children: breed_count.minted,
+    };
+    save_stats(storage, None, env.block.height, &totals)?;
+
+    let mut stats = read_stats(storage, Some(address), None)?;
+    stats.breeds += breeds;
+    stats.children += children;
+    save_stats(storage, Some(address), env.block.height, &stats)
+}
+
 // Function to read breed count
 fn read_breed_count(storage: &dyn Storage) -> StdResult<BreedCount> {
     singleton_read(storage, BREED_COUNT_KEY).load()
@@ -90,552 +373,2981 @@ fn read_breed_count(storage: &dyn Storage) -> StdResult<BreedCount> {
 
 // Function to save breed
 fn save_breed(storage: &mut dyn Storage, id: u64, breed: &Breed) -> StdResult<()> {
-    let mut key = id.to_be_bytes().to_vec();
-    key.insert(0, BREEDS_KEY);
-    storage.set(&key, &to_vec(breed)?);
-    Ok(())
+    bucket(storage, BREEDS_KEY).save(&id.to_be_bytes(), breed)
 }
 
 // Function to read breed
 fn read_breed(storage: &dyn Storage, id: u64) -> StdResult<Option<Breed>> {
-    let mut key = id.to_be_bytes().to_vec();
-    key.insert(0, BREEDS_KEY);
-    match storage.get(&key) {
-        Some(data) => Ok(Some(from_slice(&data)?)),
-        None => Ok(None),
-    }
+    bucket_read(storage, BREEDS_KEY).may_load(&id.to_be_bytes())
+}
+
+// Function to read all breeds, ordered by id
+fn read_breeds(storage: &dyn Storage) -> StdResult<Vec<Breed>> {
+    bucket_read(storage, BREEDS_KEY)
+        .range(None, None, Order::Ascending)
+        .map(|item| item.map(|(_, breed)| breed))
+        .collect()
 }
 
 // Function to remove breed
 fn remove_breed(storage: &mut dyn Storage, id: u64) -> StdResult<()> {
-    let mut key = id.to_be_bytes().to_vec();
-    key.insert(0, BREEDS_KEY);
-    storage.remove(&key);
+    bucket::<Breed>(storage, BREEDS_KEY).remove(&id.to_be_bytes());
     Ok(())
 }
 
-// Function to update config
-fn update_config(
-    deps: DepsMut,
-    env: Env,
-    info: MessageInfo,
-    breed_count_limit: Option<u32>,
-    breed_duration: Option<u64>,
-    breed_price_amount: Option<Uint128>,
-    breed_price_denom: Option<String>,
-    owner: Option<HumanAddr>,
-) -> StdResult<HandleResponse> {
-    let mut config = read_config(deps.storage)?;
-    if info.sender != config.owner {
-        return Err(StdError::Unauthorized { backtrace: None });
-    }
-    if let Some(limit) = breed_count_limit {
-        config.config.breed_count_limit = limit;
-    }
-    if let Some(duration) = breed_duration {
-        config.config.breed_duration = duration;
-    }
-    if let Some(amount) = breed_price_amount {
-        config.config.breed_price_amount = amount;
-    }
-    if let Some(denom) = breed_price_denom {
-        config.config.breed_price_denom = denom;
-    }
-    if let Some(new_owner) = owner {
-        config.owner = deps.api.canonical_address(&new_owner)?;
-    }
-    save_config(deps.storage, &config)?;
-    Ok(HandleResponse::default())
+// Function to index an unsettled breed under its owner
+fn save_owner_breed(storage: &mut dyn Storage, owner: &CanonicalAddr, breed_id: u64) -> StdResult<()> {
+    Bucket::multilevel(storage, &[OWNER_BREEDS_KEY, owner.as_slice()]).save(&breed_id.to_be_bytes(), &breed_id)
 }
 
-// Other functions such as start_breed, breed, mint, withdraw, withdraw_fund, query_config, query_breed_info, query_breeded_count, query_breed_requests_count, query_breed_finished_count, query_breedings, query_breedings_length, query_user_breedings, and sort_breedings go here...
-
-
-fn start_breed(deps: DepsMut, env: Env, info: MessageInfo) -> StdResult<HandleResponse> {
-    let config = read_config(deps.storage)?;
-    let breed_count = read_breed_count(deps.storage)?;
-
-    if breed_count.count >= config.config.breed_count_limit as u64 {
-        return Err(StdError::generic_err("Maximum breed count reached"));
-    }
+// Function to remove a breed from the index of its owner
+fn remove_owner_breed(storage: &mut dyn Storage, owner: &CanonicalAddr, breed_id: u64) {
+    Bucket::<u64>::multilevel(storage, &[OWNER_BREEDS_KEY, owner.as_slice()]).remove(&breed_id.to_be_bytes())
+}
 
-    let breed_id = breed_count.latest_id + 1;
-    let new_breed = Breed {
-        id: breed_id,
-        start_time: env.block.time,
-        nft_owner: deps.api.canonical_address(&info.sender)?,
-        nft_token_id1: String::default(), // Provide mechanism to acquire parent NFTs
-        nft_token_id2: String::default(), // Provide mechanism to acquire parent NFTs
-        end_time: env.block.time + config.config.breed_duration,
-        withdrawn: false,
-    };
+// Function to read the bucket indexing the breeds of an owner by id
+fn owner_breeds_read<'a>(storage: &'a dyn Storage, owner: &CanonicalAddr) -> ReadonlyBucket<'a, u64> {
+    ReadonlyBucket::multilevel(storage, &[OWNER_BREEDS_KEY, owner.as_slice()])
+}
 
-    save_breed(deps.storage, breed_id, &new_breed)?;
-    let updated_breed_count = BreedCount {
-        count: breed_count.count + 1,
-        latest_id: breed_id,
-    };
-    save_breed_count(deps.storage, &updated_breed_count)?;
+// Function to index an auto-claim breed until it is settled
+fn save_auto_claim_breed(storage: &mut dyn Storage, breed_id: u64) -> StdResult<()> {
+    bucket(storage, AUTO_CLAIM_BREEDS_KEY).save(&breed_id.to_be_bytes(), &breed_id)
+}
 
-    Ok(HandleResponse::default())
+// Function to remove a settled breed from the auto-claim index
+fn remove_auto_claim_breed(storage: &mut dyn Storage, breed_id: u64) {
+    bucket::<u64>(storage, AUTO_CLAIM_BREEDS_KEY).remove(&breed_id.to_be_bytes())
 }
 
-fn breed(
-    deps: DepsMut,
-    env: Env,
-    info: MessageInfo,
-    nft_token_id1: String,
-    nft_token_id2: String,
-) -> StdResult<HandleResponse> {
-    let config = read_config(deps.storage)?;
-    let breed_count = read_breed_count(deps.storage)?;
+// Function to keep a rescued receipt breed until its receipt is sent
+fn save_rescued_breed(storage: &mut dyn Storage, breed: &Breed) -> StdResult<()> {
+    bucket(storage, RESCUED_BREEDS_KEY).save(&breed.id.to_be_bytes(), breed)
+}
 
-    let breed_id = breed_count.latest_id + 1;
-    let new_breed = Breed {
-        id: breed_id,
-        start_time: env.block.time,
-        nft_owner: deps.api.canonical_address(&info.sender)?,
-        nft_token_id1,
-        nft_token_id2,
-        end_time: env.block.time + config.config.breed_duration,
-        withdrawn: false,
-    };
+// Function to read a rescued receipt breed
+fn read_rescued_breed(storage: &dyn Storage, breed_id: u64) -> StdResult<Option<Breed>> {
+    bucket_read(storage, RESCUED_BREEDS_KEY).may_load(&breed_id.to_be_bytes())
+}
 
-    save_breed(deps.storage, breed_id, &new_breed)?;
-    let updated_breed_count = BreedCount {
-        count: breed_count.count + 1,
-        latest_id: breed_id,
-    };
-    save_breed_count(deps.storage, &updated_breed_count)?;
+// Function to remove a rescued breed once its receipt is redeemed
+fn remove_rescued_breed(storage: &mut dyn Storage, breed_id: u64) {
+    bucket::<Breed>(storage, RESCUED_BREEDS_KEY).remove(&breed_id.to_be_bytes())
+}
 
-    Ok(HandleResponse::default())
+// Function to save payout table
+fn save_payout_table(storage: &mut dyn Storage, payout_table: &PayoutTable) -> StdResult<()> {
+    singleton(storage, PAYOUT_TABLE_KEY).save(payout_table)
 }
 
-fn mint(
-    deps: DepsMut,
-    env: Env,
-    info: MessageInfo,
-    extension: Option<Metadata>,
-    token_id: String,
-    token_uri: Option<String>,
-) -> StdResult<HandleResponse> {
-    // Get the contract address of the NFT contract
-    let nft_contract_address = deps.api.addr_humanize(&config.child_contract_addr)?;
+// Function to read payout table
+fn read_payout_table(storage: &dyn Storage) -> StdResult<PayoutTable> {
+    singleton_read(storage, PAYOUT_TABLE_KEY).load()
+}
 
-    // Generate the NFT instantiate message
-    let nft_instantiate_msg = NftInstantiateMsg {
-        name: "My NFT".to_string(),
-        symbol: "NFT".to_string(),
-        minter: env.contract.address.clone(),
-    };
+// Function to save fee ledger entry
+fn save_fee_ledger(storage: &mut dyn Storage, ledger: &FeeLedger) -> StdResult<()> {
+    bucket(storage, FEE_LEDGER_KEY).save(&ledger.denom.key(), ledger)
+}
 
-    // Execute the NFT instantiate message
-    let execute_msg = CosmosMsg::Wasm(WasmMsg::Instantiate {
-        code_id: 123, // Replace with the actual code ID of the NFT contract
-        send: vec![],
-        label: "nft".to_string(),
-        msg: to_binary(&nft_instantiate_msg)?,
-        funds: vec![],
-    });
+// Function to read fee ledger entry, denoms without fees yet start empty
+fn read_fee_ledger(storage: &dyn Storage, denom: &FeeDenom) -> StdResult<FeeLedger> {
+    let ledger = bucket_read(storage, FEE_LEDGER_KEY).may_load(&denom.key())?;
+    Ok(ledger.unwrap_or_else(|| FeeLedger::new(denom.clone())))
+}
 
-    // Dispatch the execute message
-    let res = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-        contract_addr: nft_contract_address.clone(),
-        msg: to_binary(&NftQueryMsg::ContractInfo {})?,
-    }))?;
+// Function to read every fee ledger entry
+fn read_fee_ledgers(storage: &dyn Storage) -> StdResult<Vec<FeeLedger>> {
+    bucket_read(storage, FEE_LEDGER_KEY)
+        .range(None, None, Order::Ascending)
+        .map(|item| item.map(|(_, ledger)| ledger))
+        .collect()
+}
 
-    // Check if the NFT contract is already instantiated
-    let contract_info: ContractInfoResponse = from_binary(&res)?;
+// Function to save the allowlist round
+fn save_allowlist(storage: &mut dyn Storage, round: &AllowlistRound) -> StdResult<()> {
+    singleton(storage, ALLOWLIST_KEY).save(round)
+}
 
-    // If NFT contract is not already instantiated, instantiate it
-    if !contract_info.is_initialized() {
-        execute(deps.as_mut(), env.clone(), info.clone(), execute_msg)?;
-    }
+// Function to read the allowlist round, if one was ever set
+fn read_allowlist(storage: &dyn Storage) -> StdResult<Option<AllowlistRound>> {
+    singleton_read(storage, ALLOWLIST_KEY).may_load()
+}
 
-    // Generate the mint NFT execute message
-    let nft_mint_msg = NftExecuteMsg::Mint {
-        token_id: token_id.clone(),
-        owner: env.message.sender.clone(),
-        uri: token_uri.clone(),
-        name: None,
-        description: None,
-        image: None,
-    };
+// Usage is keyed by round so that a new round starts every address from zero
+fn allowlist_usage_key(round_id: u64, addr: &CanonicalAddr) -> Vec<u8> {
+    [round_id.to_be_bytes().as_ref(), addr.as_slice()].concat()
+}
 
-    // Execute the mint NFT execute message
-    let execute_msg = CosmosMsg::Wasm(WasmMsg::Execute {
-        contract_addr: nft_contract_address,
-        msg: to_binary(&nft_mint_msg)?,
-        send: vec![],
-    });
+// Function to save the quota used by an address in a round
+fn save_allowlist_usage(storage: &mut dyn Storage, round_id: u64, addr: &CanonicalAddr, used: u32) -> StdResult<()> {
+    bucket(storage, ALLOWLIST_USAGE_KEY).save(&allowlist_usage_key(round_id, addr), &used)
+}
 
-    // Dispatch the execute message
-    execute(deps.as_mut(), env, info, execute_msg)
+// Function to read the quota used by an address in a round
+fn read_allowlist_usage(storage: &dyn Storage, round_id: u64, addr: &CanonicalAddr) -> StdResult<u32> {
+    let used = bucket_read(storage, ALLOWLIST_USAGE_KEY).may_load(&allowlist_usage_key(round_id, addr))?;
+    Ok(used.unwrap_or_default())
 }
 
-fn withdraw(deps: DepsMut, env: Env, info: MessageInfo, breed_id: u64) -> StdResult<HandleResponse> {
-    let config = read_config(deps.storage)?;
-    let mut breed = read_breed(deps.storage, breed_id)?;
-    let sender_address = deps.api.canonical_address(&info.sender)?;
+// Function to save a parent collection
+fn save_parent_collection(storage: &mut dyn Storage, collection: &ParentCollection) -> StdResult<()> {
+    bucket(storage, PARENT_COLLECTIONS_KEY).save(collection.addr.as_slice(), collection)
+}
 
-    if breed.is_none() {
-        return Err(StdError::NotFound { kind: "Breed".to_string() });
-    }
+// Function to read a parent collection
+fn read_parent_collection(storage: &dyn Storage, addr: &CanonicalAddr) -> StdResult<Option<ParentCollection>> {
+    bucket_read(storage, PARENT_COLLECTIONS_KEY).may_load(addr.as_slice())
+}
 
-    let breed = breed.unwrap();
+// Function to remove a parent collection
+fn remove_parent_collection_entry(storage: &mut dyn Storage, addr: &CanonicalAddr) {
+    bucket::<ParentCollection>(storage, PARENT_COLLECTIONS_KEY).remove(addr.as_slice())
+}
 
-    if sender_address != breed.nft_owner {
-        return Err(StdError::Unauthorized { backtrace: None });
-    }
+// Function to read every parent collection
+fn read_parent_collections(storage: &dyn Storage) -> StdResult<Vec<ParentCollection>> {
+    bucket_read(storage, PARENT_COLLECTIONS_KEY)
+        .range(None, None, Order::Ascending)
+        .map(|item| item.map(|(_, collection)| collection))
+        .collect()
+}
 
-    if env.block.time < breed.end_time {
-        return Err(StdError::generic_err("Breed process has not yet finished"));
-    }
+// A pairing is stored once under its collections in sorted order, so A x B and B x A share a key
+fn pairing_key(collection1: &CanonicalAddr, collection2: &CanonicalAddr) -> Vec<u8> {
+    let (first, second) = if collection1.as_slice() <= collection2.as_slice() {
+        (collection1, collection2)
+    } else {
+        (collection2, collection1)
+    };
+    [&[first.len() as u8], first.as_slice(), second.as_slice()].concat()
+}
 
-    if breed.withdrawn {
-        return Err(StdError::generic_err("Breed process has already been withdrawn"));
-    }
+// Function to save a pairing
+fn save_pairing(storage: &mut dyn Storage, pairing: &Pairing) -> StdResult<()> {
+    bucket(storage, PAIRINGS_KEY).save(&pairing_key(&pairing.collection1, &pairing.collection2), pairing)
+}
 
-    // Logic for withdrawing NFTs and handling funds
+// Function to read the pairing of two collections
+fn read_pairing(storage: &dyn Storage, collection1: &CanonicalAddr, collection2: &CanonicalAddr) -> StdResult<Option<Pairing>> {
+    bucket_read(storage, PAIRINGS_KEY).may_load(&pairing_key(collection1, collection2))
+}
 
-    breed.withdrawn = true;
-    save_breed(deps.storage, breed_id, &breed)?;
+// Function to remove the pairing of two collections
+fn remove_pairing_entry(storage: &mut dyn Storage, collection1: &CanonicalAddr, collection2: &CanonicalAddr) {
+    bucket::<Pairing>(storage, PAIRINGS_KEY).remove(&pairing_key(collection1, collection2))
+}
 
-    Ok(HandleResponse::default())
+// Function to read every pairing
+fn read_pairings(storage: &dyn Storage) -> StdResult<Vec<Pairing>> {
+    bucket_read(storage, PAIRINGS_KEY)
+        .range(None, None, Order::Ascending)
+        .map(|item| item.map(|(_, pairing)| pairing))
+        .collect()
 }
 
-fn withdraw_fund(deps: DepsMut, env: Env, info: MessageInfo) -> StdResult<HandleResponse> {
-    // Ensure only the contract owner can withdraw funds
-    if info.sender != config.owner {
-        return Err(StdError::Unauthorized {});
-    }
+fn parent_token_key(collection: &CanonicalAddr, token_id: &str) -> Vec<u8> {
+    [&[collection.len() as u8], collection.as_slice(), token_id.as_bytes()].concat()
+}
 
-    // Get the balance of the contract
-    let contract_balance = deps.querier.query_balance(&env.contract.address)?;
+// Function to save how many children a parent token has
+fn save_breeded_count(storage: &mut dyn Storage, collection: &CanonicalAddr, token_id: &str, count: u32) -> StdResult<()> {
+    bucket(storage, BREEDED_COUNT_KEY).save(&parent_token_key(collection, token_id), &count)
+}
 
-    // Ensure the contract has sufficient funds to withdraw
-    if contract_balance.amount.is_zero() {
-        return Err(StdError::generic_err("Contract balance is zero"));
-    }
+// Function to read how many children a parent token has
+fn read_breeded_count(storage: &dyn Storage, collection: &CanonicalAddr, token_id: &str) -> StdResult<u32> {
+    let count = bucket_read(storage, BREEDED_COUNT_KEY).may_load(&parent_token_key(collection, token_id))?;
+    Ok(count.unwrap_or_default())
+}
 
-    // Specify the recipient to transfer the funds to
-    let recipient = info.sender.clone(); // Replace with the actual recipient address
+// Function to save breed offer
+fn save_breed_offer(storage: &mut dyn Storage, offer: &BreedOffer) -> StdResult<()> {
+    bucket(storage, BREED_OFFERS_KEY).save(&offer.breed_id.to_be_bytes(), offer)
+}
 
-    // Generate the execute message to transfer funds
-    let execute_msg = CosmosMsg::Bank(BankMsg::Send {
-        to_address: recipient.into(),
-        amount: vec![coin(contract_balance.amount, contract_balance.denom.clone())],
-    });
+// Function to read breed offer
+fn read_breed_offer(storage: &dyn Storage, breed_id: u64) -> StdResult<Option<BreedOffer>> {
+    bucket_read(storage, BREED_OFFERS_KEY).may_load(&breed_id.to_be_bytes())
+}
 
-    // Dispatch the execute message
-    execute(deps, env, info, execute_msg)
+// Function to remove breed offer
+fn remove_breed_offer(storage: &mut dyn Storage, breed_id: u64) {
+    bucket::<BreedOffer>(storage, BREED_OFFERS_KEY).remove(&breed_id.to_be_bytes());
 }
 
-fn query_config(deps: Deps) -> ConfigResponse {
-    let config = read_config(deps.storage).unwrap();
-    ConfigResponse {
-        breed_count_limit: config.config.breed_count_limit,
-        breed_duration: config.config.breed_duration,
-        breed_price_amount: config.config.breed_price_amount,
-        breed_price_denom: config.config.breed_price_denom,
-        breed_start_time: config.config.breed_start_time,
-        child_base_uri: config.config.child_base_uri,
-        child_contract_addr: deps.api.human_address(&config.config.child_contract_addr).unwrap(),
-        child_nft_max_supply: config.config.child_nft_max_supply,
-        owner: deps.api.human_address(&config.owner).unwrap(),
-        parent_contract_addr: deps.api.human_address(&config.config.parent_contract_addr).unwrap(),
-    }
+// Function to save hook
+fn save_hook(storage: &mut dyn Storage, hook: &Hook) -> StdResult<()> {
+    bucket(storage, HOOKS_KEY).save(hook.addr.as_slice(), hook)
 }
 
-fn query_breed_info(deps: Deps, breed_id: u64) -> BreedInfoResponse {
-    let breed = read_breed(deps.storage, breed_id).unwrap().unwrap();
-    BreedInfoResponse {
-        child_token_id: None, // Provide mechanism to query child NFT details
-        end_time: breed.end_time,
-        nft_owner: deps.api.human_address(&breed.nft_owner).unwrap(),
-        nft_token_id1: breed.nft_token_id1,
-        nft_token_id2: breed.nft_token_id2,
-        start_time: breed.start_time,
-        withdrawn: breed.withdrawn,
-    }
+// Function to read hook
+fn read_hook(storage: &dyn Storage, addr: &CanonicalAddr) -> StdResult<Option<Hook>> {
+    bucket_read(storage, HOOKS_KEY).may_load(addr.as_slice())
 }
 
-fn query_breed_requests_count(deps: Deps) -> BreedRequestsCountResponse {
-    // Load the contract state
-    let state = config_read(deps.storage).load().unwrap_or_default();
+// Function to remove hook
+fn remove_hook_entry(storage: &mut dyn Storage, addr: &CanonicalAddr) {
+    bucket::<Hook>(storage, HOOKS_KEY).remove(addr.as_slice())
+}
 
-    // Access the breed requests count from the contract state
-    state.breed_requests_count
+// Function to read every hook
+fn read_hooks(storage: &dyn Storage) -> StdResult<Vec<Hook>> {
+    bucket_read(storage, HOOKS_KEY)
+        .range(None, None, Order::Ascending)
+        .map(|item| item.map(|(_, hook)| hook))
+        .collect()
 }
 
-fn query_breed_finished_count(deps: Deps) -> BreedFinishedCountResponse {
-    // Load the contract state
-    let state = config_read(deps.storage).load().unwrap_or_default();
+// Function to save child info
+fn save_child_info(storage: &mut dyn Storage, token_id: &str, info: &ChildInfo) -> StdResult<()> {
+    bucket(storage, CHILD_INFOS_KEY).save(token_id.as_bytes(), info)
+}
 
-    // Access the breed finished count from the contract state
-    state.breed_finished_count
+// Function to read child info
+fn read_child_info(storage: &dyn Storage, token_id: &str) -> StdResult<Option<ChildInfo>> {
+    bucket_read(storage, CHILD_INFOS_KEY).may_load(token_id.as_bytes())
 }
 
-fn query_breeded_count(deps: Deps, parent_nft_token_id: String) -> BreededCountResponse {
-    // Load the contract state
-    let state = config(deps.storage).load().unwrap_or_default();
+// Function to save child royalty
+fn save_child_royalty(storage: &mut dyn Storage, token_id: &str, royalty: &ChildRoyalty) -> StdResult<()> {
+    bucket(storage, CHILD_ROYALTIES_KEY).save(token_id.as_bytes(), royalty)
+}
 
-    // Access the breeded count for the specified parent NFT token ID from the contract state
-    // Assuming breeded_counts is a map in your contract state storing the breeded count for each parent NFT token ID
-    let breeded_counts = state.breeded_counts;
+// Function to read child royalty
+fn read_child_royalty(storage: &dyn Storage, token_id: &str) -> StdResult<Option<ChildRoyalty>> {
+    bucket_read(storage, CHILD_ROYALTIES_KEY).may_load(token_id.as_bytes())
+}
 
-    // Get the breeded count for the specified parent NFT token ID
-    let breeded_count = breeded_counts.get(&parent_nft_token_id).cloned().unwrap_or_default();
+// Leaderboard entries are keyed by address, or by parent token on the parents leaderboard
+fn leaderboard_entry_key(address: &CanonicalAddr, token_id: Option<&str>) -> Vec<u8> {
+    match token_id {
+        Some(token_id) => parent_token_key(address, token_id),
+        None => address.as_slice().to_vec(),
+    }
+}
 
-    breeded_count
+// Ranks sort by score then entry key, so a descending range lists the highest scores first
+fn leaderboard_rank_key(score: Uint128, entry_key: &[u8]) -> Vec<u8> {
+    [score.u128().to_be_bytes().as_ref(), entry_key].concat()
 }
 
-fn query_breedings(
-    deps: Deps,
-    count: u32,
-    from: u32,
-    sort: String,
-) -> QueryBreedingsResponse {
-    // Load the contract state or query from external storage if needed
-    let state = config_read(deps.storage).load().unwrap_or_default();
+// Function to update a leaderboard score, moving the entry in the sorted ranks
+fn update_leaderboard<F>(storage: &mut dyn Storage, kind: &LeaderboardKind, address: &CanonicalAddr, token_id: Option<&str>, update: F) -> StdResult<()>
+where
+    F: FnOnce(Uint128) -> StdResult<Uint128>,
+{
+    let namespace = kind.key();
+    let entry_key = leaderboard_entry_key(address, token_id);
+    let previous = ReadonlyBucket::<Uint128>::multilevel(storage, &[LEADERBOARD_SCORES_KEY, &namespace])
+        .may_load(&entry_key)?
+        .unwrap_or_default();
+    let score = update(previous)?;
+
+    let mut ranks = Bucket::<LeaderboardEntry>::multilevel(storage, &[LEADERBOARD_RANKS_KEY, &namespace]);
+    ranks.remove(&leaderboard_rank_key(previous, &entry_key));
+    if !score.is_zero() {
+        let entry = LeaderboardEntry {
+            address: address.clone(),
+            token_id: token_id.map(|token_id| token_id.to_string()),
+            score,
+        };
+        ranks.save(&leaderboard_rank_key(score, &entry_key), &entry)?;
+    }
 
-    // Retrieve all breedings from the contract state or external storage
-    let all_breedings: Vec<BreedInfo> = unimplemented!(); // Replace with your logic to retrieve all breedings
+    let mut scores = Bucket::<Uint128>::multilevel(storage, &[LEADERBOARD_SCORES_KEY, &namespace]);
+    if score.is_zero() {
+        scores.remove(&entry_key);
+        Ok(())
+    } else {
+        scores.save(&entry_key, &score)
+    }
+}
 
-    // Sort the breedings based on the provided sort criteria
-    let sorted_breedings = sort_breedings(all_breedings, &sort);
+// Function to read the top entries of a leaderboard ranked below `start_after`, along with the
+// number of entries ranked above them
+fn read_leaderboard(storage: &dyn Storage, kind: &LeaderboardKind, start_after: Option<&[u8]>, limit: usize) -> StdResult<(usize, Vec<LeaderboardEntry>)> {
+    let ranks = ReadonlyBucket::<LeaderboardEntry>::multilevel(storage, &[LEADERBOARD_RANKS_KEY, &kind.key()]);
+    // The start bound is inclusive and the end bound exclusive, so the `start_after` entry counts as above
+    let above = match start_after {
+        Some(start_after) => ranks.range(Some(start_after), None, Order::Ascending).count(),
+        None => 0,
+    };
+    let entries = ranks
+        .range(None, start_after, Order::Descending)
+        .take(limit)
+        .map(|item| item.map(|(_, entry)| entry))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok((above, entries))
+}
 
-    // Slice the breedings based on the provided count and from parameters
-    let start_index = from as usize;
-    let end_index = (from + count) as usize;
-    let sliced_breedings = sorted_breedings[start_index..end_index].to_vec();
+// Function to save the target of the child collection being instantiated
+fn save_pending_child_collection(storage: &mut dyn Storage, target: &ChildCollectionTarget) -> StdResult<()> {
+    singleton(storage, PENDING_CHILD_COLLECTION_KEY).save(target)
+}
 
-    // Return the sliced breedings as the response
-    sliced_breedings
+// Function to take the target of the child collection being instantiated
+fn take_pending_child_collection(storage: &mut dyn Storage) -> StdResult<ChildCollectionTarget> {
+    let target = singleton_read(storage, PENDING_CHILD_COLLECTION_KEY).load()?;
+    singleton::<ChildCollectionTarget>(storage, PENDING_CHILD_COLLECTION_KEY).remove();
+    Ok(target)
 }
 
-fn sort_breedings(breedings: Vec<BreedInfo>, sort: &str) -> Vec<BreedInfo> {
-    let mut sorted_breedings = breedings;
+// Function to update config
+#[allow(clippy::too_many_arguments)]
+fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    breed_count_limit: Option<u32>,
+    breed_duration: Option<u64>,
+    breed_price_amount: Option<Uint128>,
+    breed_price_denom: Option<String>,
+    breed_price_cw20: Option<String>,
+    pricing: Option<PricingMode>,
+    max_batch_size: Option<u32>,
+    child_token_id_scheme: Option<ChildTokenIdScheme>,
+    child_base_uri: Option<String>,
+    child_uri_template: Option<String>,
+    royalty: Option<RoyaltyMsg>,
+    owner: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut config = read_config(deps.storage)?;
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    let mut attributes = vec![attr(events::ACTION, events::ACTION_UPDATE_CONFIG)];
+    attributes.extend(apply_config_update(
+        deps.as_ref(),
+        &mut config.config,
+        breed_count_limit,
+        breed_duration,
+        breed_price_amount,
+        breed_price_denom,
+        breed_price_cw20,
+        pricing,
+        max_batch_size,
+        child_token_id_scheme,
+        child_base_uri,
+        child_uri_template,
+        royalty,
+    )?);
+    // An ownership change is reported with the config update, under its action
+    if let Some(new_owner) = owner {
+        attributes.extend(replace_owner(deps.as_ref(), &mut config, new_owner)?);
+    }
+    save_config(deps.storage, &config)?;
+    Ok(Response {
+        attributes,
+        ..Response::default()
+    })
+}
 
-    // Sort the breedings based on the provided criteria
-    match sort.as_str() {
-        "ascending" => {
-            sorted_breedings.sort_by(|a, b| a.start_time.cmp(&b.start_time));
+// Applies the given config fields, shared by the owner and governance, returns their attributes
+#[allow(clippy::too_many_arguments)]
+fn apply_config_update(
+    deps: Deps,
+    config: &mut Config,
+    breed_count_limit: Option<u32>,
+    breed_duration: Option<u64>,
+    breed_price_amount: Option<Uint128>,
+    breed_price_denom: Option<String>,
+    breed_price_cw20: Option<String>,
+    pricing: Option<PricingMode>,
+    max_batch_size: Option<u32>,
+    child_token_id_scheme: Option<ChildTokenIdScheme>,
+    child_base_uri: Option<String>,
+    child_uri_template: Option<String>,
+    royalty: Option<RoyaltyMsg>,
+) -> Result<Vec<Attribute>, ContractError> {
+    let mut attributes = vec![];
+    if let Some(limit) = breed_count_limit {
+        config.breed_count_limit = limit;
+        attributes.push(attr(events::BREED_COUNT_LIMIT, limit));
+    }
+    if let Some(duration) = breed_duration {
+        config.breed_duration = duration;
+        attributes.push(attr(events::BREED_DURATION, duration));
+    }
+    if let Some(amount) = breed_price_amount {
+        config.breed_price_amount = amount;
+        attributes.push(attr(events::BREED_PRICE_AMOUNT, amount));
+    }
+    if let Some(denom) = breed_price_denom {
+        attributes.push(attr(events::BREED_PRICE_DENOM, &denom));
+        config.breed_price_denom = denom;
+        config.breed_price_cw20 = None;
+    }
+    if let Some(token) = breed_price_cw20 {
+        config.breed_price_cw20 = Some(deps.api.addr_canonicalize(&token)?);
+        attributes.push(attr(events::BREED_PRICE_CW20, token));
+    }
+    if let Some(pricing) = pricing {
+        validate_pricing(&pricing)?;
+        attributes.push(attr(events::PRICING, to_binary(&pricing)?.to_base64()));
+        config.pricing = pricing;
+    }
+    if let Some(size) = max_batch_size {
+        config.max_batch_size = size;
+        attributes.push(attr(events::MAX_BATCH_SIZE, size));
+    }
+    // The child naming is validated as a whole, an empty base uri or template unsets it
+    if child_token_id_scheme.is_some() || child_base_uri.is_some() || child_uri_template.is_some() {
+        if let Some(scheme) = child_token_id_scheme {
+            attributes.push(attr(events::CHILD_TOKEN_ID_SCHEME, to_binary(&scheme)?.to_base64()));
+            config.child_token_id_scheme = scheme;
         }
-        "descending" => {
-            sorted_breedings.sort_by(|a, b| b.start_time.cmp(&a.start_time));
+        if let Some(base_uri) = child_base_uri {
+            attributes.push(attr(events::CHILD_BASE_URI, &base_uri));
+            config.child_base_uri = Some(base_uri).filter(|uri| !uri.is_empty());
         }
-        _ => {
-            // Default sorting logic if criteria is not recognized
-            // For example, you might sort by default based on the start_time in ascending order
-            sorted_breedings.sort_by(|a, b| a.start_time.cmp(&b.start_time));
+        if let Some(template) = child_uri_template {
+            attributes.push(attr(events::CHILD_URI_TEMPLATE, &template));
+            config.child_uri_template = Some(template).filter(|template| !template.is_empty());
         }
+        validate_child_naming(&config.child_token_id_scheme, &config.child_base_uri, &config.child_uri_template)?;
     }
-
-    sorted_breedings
+    // Only children minted afterwards are affected, minted ones keep their royalty
+    if let Some(royalty) = royalty {
+        attributes.push(attr(events::ROYALTY_PAYMENT_ADDRESS, &royalty.payment_address));
+        attributes.push(attr(events::ROYALTY_PERCENTAGE, royalty.percentage));
+        attributes.push(attr(events::ROYALTY_PARENT_SHARE_BPS, royalty.parent_share_bps));
+        config.royalty = royalty_config(deps, royalty)?;
+    }
+    Ok(attributes)
 }
 
-fn query_breedings_length(deps: Deps) -> QueryBreedingsLengthResponse {
-    // Load the contract state or query from external storage if needed
-    let state = config_read(deps.storage).load().unwrap_or_default();
+// Returns the ownership attributes without an action, callers emit their own
+fn replace_owner(deps: Deps, config: &mut ConfigState, new_owner: String) -> Result<Vec<Attribute>, ContractError> {
+    let previous_owner = deps.api.addr_humanize(&config.owner)?;
+    config.owner = deps.api.addr_canonicalize(&new_owner)?;
+    Ok(vec![
+        attr(events::PREVIOUS_OWNER, previous_owner),
+        attr(events::NEW_OWNER, new_owner),
+    ])
+}
 
-    // Access the length of breed processes from the contract state
-    let breedings_length = state.breedings.len() as u32;
+fn sudo_force_pause(deps: DepsMut, paused: bool) -> Result<Response, ContractError> {
+    let mut config = read_config(deps.storage)?;
+    config.config.paused = paused;
+    save_config(deps.storage, &config)?;
 
-    breedings_length
+    Ok(Response {
+        attributes: vec![
+            attr(events::ACTION, events::ACTION_FORCE_PAUSE),
+            attr(events::PAUSED, paused),
+        ],
+        ..Response::default()
+    })
 }
 
-fn query_user_breedings(
-    deps: Deps,
-    count: u32,
-    from: u32,
-    sort: String,
-    user: HumanAddr,
-) -> QueryUserBreedingsResponse {
-    // Load the contract state or query from external storage if needed
-    let state = config_read(deps.storage).load().unwrap_or_default();
-
-    // Retrieve all breed processes associated with the specified user
-    let user_breedings: Vec<BreedInfo> = state
-        .breedings
-        .iter()
-        .filter(|breeding| breeding.user == user)
-        .cloned()
-        .collect();
+fn sudo_replace_owner(deps: DepsMut, owner: String) -> Result<Response, ContractError> {
+    let mut config = read_config(deps.storage)?;
+    let mut attributes = vec![attr(events::ACTION, events::ACTION_CHANGE_OWNERSHIP)];
+    attributes.extend(replace_owner(deps.as_ref(), &mut config, owner)?);
+    save_config(deps.storage, &config)?;
 
-    // Sort the breedings based on the provided sort criteria
-    let sorted_breedings = sort_breedings(user_breedings, &sort);
+    Ok(Response {
+        attributes,
+        ..Response::default()
+    })
+}
 
-    // Slice the breedings based on the provided count and from parameters
-    let start_index = from as usize;
-    let end_index = (from + count) as usize;
-    let sliced_breedings = sorted_breedings[start_index..end_index].to_vec();
+#[allow(clippy::too_many_arguments)]
+fn sudo_update_config(
+    deps: DepsMut,
+    breed_count_limit: Option<u32>,
+    breed_duration: Option<u64>,
+    breed_price_amount: Option<Uint128>,
+    breed_price_denom: Option<String>,
+    breed_price_cw20: Option<String>,
+    pricing: Option<PricingMode>,
+    max_batch_size: Option<u32>,
+    child_token_id_scheme: Option<ChildTokenIdScheme>,
+    child_base_uri: Option<String>,
+    child_uri_template: Option<String>,
+    royalty: Option<RoyaltyMsg>,
+    child_contract_addr: Option<String>,
+    receipt_contract_addr: Option<String>,
+    paused: Option<bool>,
+) -> Result<Response, ContractError> {
+    let mut config = read_config(deps.storage)?;
+    let mut attributes = vec![attr(events::ACTION, events::ACTION_UPDATE_CONFIG)];
+    attributes.extend(apply_config_update(
+        deps.as_ref(),
+        &mut config.config,
+        breed_count_limit,
+        breed_duration,
+        breed_price_amount,
+        breed_price_denom,
+        breed_price_cw20,
+        pricing,
+        max_batch_size,
+        child_token_id_scheme,
+        child_base_uri,
+        child_uri_template,
+        royalty,
+    )?);
+    // Only governance can repoint the default child and receipt collections, started breeds keep
+    // the receipt collection they were minted in
+    if let Some(addr) = child_contract_addr {
+        config.config.child_contract_addr = Some(deps.api.addr_canonicalize(&addr)?);
+        attributes.push(attr(events::CHILD_CONTRACT, addr));
+    }
+    if let Some(addr) = receipt_contract_addr {
+        config.config.receipt_contract_addr = Some(deps.api.addr_canonicalize(&addr)?);
+        attributes.push(attr(events::RECEIPT_CONTRACT, addr));
+    }
+    if let Some(paused) = paused {
+        config.config.paused = paused;
+        attributes.push(attr(events::PAUSED, paused));
+    }
+    save_config(deps.storage, &config)?;
 
-    // Return the sliced breedings as the response
-    sliced_breedings
+    Ok(Response {
+        attributes,
+        ..Response::default()
+    })
 }
 
-fn sort_breedings(breedings: Vec<BreedInfo>, sort: &str) -> Vec<BreedInfo> {
-    let mut sorted_breedings = breedings;
+// Sends the escrowed parents of a breed to `recipient`, or to its owner, and refunds the fee and
+// tip to the breeder. The breed is removed like a cancellation without its time limit, which also
+// drops its offer. Without a recipient the parents of a receipt breed stay escrowed until the
+// receipt is sent here, a recipient is only needed when the receipt collection no longer works.
+fn sudo_rescue_nfts(deps: DepsMut, env: Env, breed_id: u64, recipient: Option<String>) -> Result<Response, ContractError> {
+    let breed = match read_breed(deps.storage, breed_id)? {
+        Some(breed) => breed,
+        None => return Err(ContractError::not_found("Breed")),
+    };
 
-    // Sort the breedings based on the provided criteria
-    match sort.as_str() {
-        "ascending" => {
-            sorted_breedings.sort_by(|a, b| a.start_time.cmp(&b.start_time));
-        }
-        "descending" => {
-            sorted_breedings.sort_by(|a, b| b.start_time.cmp(&a.start_time));
-        }
-        _ => {
-            // Default sorting logic if criteria is not recognized
-            // For example, you might sort by default based on the start_time in ascending order
-            sorted_breedings.sort_by(|a, b| a.start_time.cmp(&b.start_time));
+    // Withdrawn breeds have already returned their parents
+    if breed.is_withdrawn {
+        return Err(ContractError::AlreadyWithdrawn { breed_id });
+    }
+
+    let recipient = match recipient {
+        Some(recipient) => Some(deps.api.addr_validate(&recipient)?),
+        None if breed.receipt_contract_addr.is_some() => None,
+        None => Some(deps.api.addr_humanize(&breed.owner)?),
+    };
+    let (fee, tip) = remove_unsettled_breed(deps.storage, &env, &breed)?;
+
+    let payer = deps.api.addr_humanize(breed_payer(&breed))?;
+    let mut messages = match &recipient {
+        Some(recipient) => return_parents_msgs(deps.as_ref(), &breed, recipient.as_str())?,
+        None => {
+            save_rescued_breed(deps.storage, &breed)?;
+            vec![]
         }
+    };
+    if !(fee + tip).is_zero() {
+        messages.push(fee_transfer_msg(deps.as_ref(), &breed.fee_denom, fee + tip, payer.to_string())?);
     }
 
-    sorted_breedings
-}
+    let mut attributes = breed_attributes(deps.as_ref(), events::ACTION_RESCUE_NFTS, &breed)?;
+    if let Some(recipient) = recipient {
+        attributes.push(attr(events::RECIPIENT, recipient));
+    }
+    attributes.push(attr(events::REFUND_RECIPIENT, &payer));
+    attributes.push(attr(events::AMOUNT, fee));
+    attributes.push(attr(events::DENOM, fee_denom_label(deps.as_ref(), &breed.fee_denom)?));
+    if !tip.is_zero() {
+        attributes.push(attr(events::TIP, tip));
+    }
 
-#[derive(Clone, PartialEq, ::prost::Message)]
-pub struct Config {
-    #[prost(uint32, tag = "1")]
-    pub breed_count_limit: u32,
-    #[prost(uint64, tag = "2")]
-    pub breed_duration: u64,
-    #[prost(string, tag = "3")]
-    pub breed_price_amount: String,
-    #[prost(string, tag = "4")]
-    pub breed_price_denom: String,
-    #[prost(uint64, tag = "5")]
-    pub breed_start_time: u64,
-    #[prost(string, tag = "6")]
-    pub child_base_uri: String,
-    #[prost(string, tag = "7")]
-    pub child_contract_addr: String,
-    #[prost(uint32, tag = "8")]
-    pub child_nft_max_supply: u32,
-    #[prost(string, tag = "9")]
-    pub owner: String,
-    #[prost(string, tag = "10")]
-    pub parent_contract_addr: String,
+    let submessages = hook_submsgs(
+        deps.as_ref(),
+        BreedingHookMsg::BreedCancelled {
+            breed_id,
+            owner: deps.api.addr_humanize(&breed.owner)?.to_string(),
+        },
+    )?;
+
+    Ok(Response {
+        submessages,
+        messages,
+        attributes,
+        ..Response::default()
+    })
 }
 
-#[derive(Clone, PartialEq, ::prost::Message)]
-pub struct Breed {
-    #[prost(uint64, tag = "1")]
-    pub id: u64,
-    #[prost(uint64, tag = "2")]
-    pub start_time: u64,
-    #[prost(string, tag = "3")]
-    pub nft_owner: String,
-    #[prost(string, tag = "4")]
-    pub nft_token_id1: String,
-    #[prost(string, tag = "5")]
-    pub nft_token_id2: String,
-    #[prost(uint64, tag = "6")]
-    pub end_time: u64,
-    #[prost(bool, tag = "7")]
-    pub withdrawn: bool,
+// Stepped tiers must be listed by strictly increasing `min_minted`
+fn validate_pricing(pricing: &PricingMode) -> Result<(), ContractError> {
+    if let PricingMode::Stepped { tiers } = pricing {
+        if tiers.is_empty() {
+            return Err(ContractError::InvalidPricing {
+                reason: "stepped pricing needs at least one tier".to_string(),
+            });
+        }
+        if tiers.windows(2).any(|pair| pair[0].min_minted >= pair[1].min_minted) {
+            return Err(ContractError::InvalidPricing {
+                reason: "tiers must be sorted by increasing min_minted".to_string(),
+            });
+        }
+    }
+    Ok(())
 }
 
-#[derive(Clone, PartialEq, ::prost::Message)]
-pub struct BreedCount {
-    #[prost(uint64, tag = "1")]
-    pub count: u64,
-    #[prost(uint64, tag = "2")]
-    pub latest_id: u64,
+fn validate_child_naming(
+    scheme: &ChildTokenIdScheme,
+    base_uri: &Option<String>,
+    template: &Option<String>,
+) -> Result<(), ContractError> {
+    let invalid = |reason: &str| Err(ContractError::InvalidChildNaming { reason: reason.to_string() });
+    match scheme {
+        // A trailing digit would let `{prefix}{id}` of two ids collide, like `a1` + `1` and `a` + `11`
+        ChildTokenIdScheme::Sequential { prefix } if prefix.ends_with(|c: char| c.is_ascii_digit()) => {
+            return invalid("sequential prefix must not end with a digit");
+        }
+        ChildTokenIdScheme::Padded { width } if *width == 0 || *width > MAX_PADDED_WIDTH => {
+            return invalid("padded width must be between 1 and 20");
+        }
+        _ => {}
+    }
+    if let Some(template) = template {
+        if !template.contains(CHILD_URI_ID) {
+            return invalid("uri template must contain {id}");
+        }
+        if template.contains(CHILD_URI_BASE) && base_uri.is_none() {
+            return invalid("uri template uses {base} without a base uri");
+        }
+        let rest = template.replace(CHILD_URI_BASE, "").replace(CHILD_URI_ID, "");
+        if rest.contains('{') || rest.contains('}') {
+            return invalid("uri template only accepts the {base} and {id} placeholders");
+        }
+    }
+    Ok(())
 }
 
-#[derive(Clone, PartialEq, ::prost::Message)]
-pub struct ConfigState {
-    #[prost(message, optional, tag = "1")]
-    pub config: Option<Config>,
-    #[prost(string, tag = "2")]
-    pub owner: String,
+// Token id of the child numbered `id`, `parents` are the parent token ids of a bred child
+fn child_token_id(scheme: &ChildTokenIdScheme, id: u64, parents: Option<(&str, &str)>) -> String {
+    match scheme {
+        ChildTokenIdScheme::Sequential { prefix } => format!("{}{}", prefix, id),
+        ChildTokenIdScheme::Padded { width } => format!("{:0width$}", id, width = *width as usize),
+        ChildTokenIdScheme::Hash {} => {
+            let (token_id1, token_id2) = parents.unwrap_or(("", ""));
+            hex::encode(Sha256::digest(format!("{}:{}:{}", token_id1, token_id2, id).as_bytes()))
+        }
+    }
 }
 
-#[derive(Clone, PartialEq, ::prost::Message)]
-pub struct BreedInfoResponse {
-    #[prost(string, tag = "1")]
-    pub child_token_id: String,
-    #[prost(uint64, tag = "2")]
-    pub end_time: u64,
-    #[prost(string, tag = "3")]
-    pub nft_owner: String,
-    #[prost(string, tag = "4")]
-    pub nft_token_id1: String,
-    #[prost(string, tag = "5")]
-    pub nft_token_id2: String,
-    #[prost(uint64, tag = "6")]
-    pub start_time: u64,
-    #[prost(bool, tag = "7")]
-    pub withdrawn: bool,
+// Token uri of a child, none without a template
+fn child_token_uri(base_uri: &Option<String>, template: &Option<String>, token_id: &str) -> Option<String> {
+    template.as_ref().map(|template| {
+        template
+            .replace(CHILD_URI_BASE, base_uri.as_deref().unwrap_or_default())
+            .replace(CHILD_URI_ID, token_id)
+    })
 }
 
-#[derive(Clone, PartialEq, ::prost::Message)]
-pub struct QueryBreedingsResponse {
-    #[prost(message, repeated, tag = "1")]
-    pub breed_info: Vec<BreedInfoResponse>,
-}
-
-pub enum HandleMsg {
-    UpdateConfig {
-        breed_count_limit: Option<u32>,
-        breed_duration: Option<u64>,
-        breed_price_amount: Option<Uint128>,
-        breed_price_denom: Option<String>,
-        owner: Option<HumanAddr>,
-    },
-    StartBreed {},
-    Breed {
-        nft_token_id1: String,
-        nft_token_id2: String,
-    },
-    Mint {
-        extension: Option<Metadata>,
-        token_id: String,
-        token_uri: Option<String>,
-    },
-    Withdraw {
-        breed_id: u64,
-    },
-    WithdrawFund {},
-}
-
-pub enum QueryMsg {
-    Config {},
-    BreedInfo {
-        breed_id: u64,
-    },
-    BreededCount {
-        parent_nft_token_id: String,
-    },
-    BreedRequestsCount {},
-    BreedFinishedCount {},
-    QueryBreedings {
-        count: u32,
-        from: u32,
-        sort: String,
-    },
-    QueryBreedingsLength {},
-    QueryUserBreedings {
-        count: u32,
-        from: u32,
-        sort: String,
-        user: HumanAddr,
-    },
-    QueryUserBreedingsLength {
-        user: HumanAddr,
-    },
-}
-
-pub type BreedFinishedCountResponse = u32;
-pub type Addr = String;
-pub type BreedRequestsCountResponse = u32;
-pub type BreededCountResponse = u32;
-pub type Uint128 = String;
-pub type QueryBreedingsLengthResponse = u32;
-pub type QueryUserBreedingsLengthResponse = u32;
-pub type QueryUserBreedingsResponse = Vec<BreedInfoResponse>;
-pub type Attribute = Vec<u8>;
-pub type Metadata = Vec<u8>;
+fn validate_metadata(metadata: &Metadata) -> Result<(), ContractError> {
+    let invalid = |reason: String| Err(ContractError::InvalidMetadata { reason });
+    let check_length = |field: &str, value: &str, max: usize| {
+        if value.len() > max {
+            return invalid(format!("{} is longer than {} bytes", field, max));
+        }
+        Ok(())
+    };
+
+    match metadata.name.as_deref() {
+        Some(name) if !name.trim().is_empty() => check_length("name", name, MAX_METADATA_NAME_LENGTH)?,
+        _ => return invalid("name is required".to_string()),
+    }
+    if let Some(description) = &metadata.description {
+        check_length("description", description, MAX_METADATA_DESCRIPTION_LENGTH)?;
+    }
+    for (field, url) in [
+        ("image", &metadata.image),
+        ("animation_url", &metadata.animation_url),
+        ("external_url", &metadata.external_url),
+    ]
+    .iter()
+    {
+        if let Some(url) = url {
+            check_length(field, url, MAX_METADATA_URL_LENGTH)?;
+        }
+    }
+
+    let traits = metadata.attributes.as_deref().unwrap_or_default();
+    if traits.len() > MAX_METADATA_TRAITS {
+        return invalid(format!("at most {} attributes are allowed", MAX_METADATA_TRAITS));
+    }
+    for Trait { display_type, trait_type, value } in traits {
+        if trait_type.trim().is_empty() || value.trim().is_empty() {
+            return invalid("attributes need a trait_type and a value".to_string());
+        }
+        check_length("trait_type", trait_type, MAX_TRAIT_TYPE_LENGTH)?;
+        check_length("value", value, MAX_TRAIT_VALUE_LENGTH)?;
+        if let Some(display_type) = display_type {
+            check_length("display_type", display_type, MAX_TRAIT_TYPE_LENGTH)?;
+        }
+    }
+    Ok(())
+}
+
+// Validates a royalty, a zero percentage means no royalty
+fn royalty_config(deps: Deps, royalty: RoyaltyMsg) -> Result<Option<RoyaltyConfig>, ContractError> {
+    if royalty.percentage > MAX_ROYALTY_PERCENTAGE {
+        return Err(ContractError::InvalidRoyalty {
+            reason: "percentage cannot exceed 100".to_string(),
+        });
+    }
+    if royalty.parent_share_bps > BPS_TOTAL {
+        return Err(ContractError::InvalidRoyalty {
+            reason: "parent share cannot exceed 10000 basis points".to_string(),
+        });
+    }
+    if royalty.percentage == 0 {
+        return Ok(None);
+    }
+    Ok(Some(RoyaltyConfig {
+        payment_address: deps.api.addr_canonicalize(&royalty.payment_address)?,
+        percentage: royalty.percentage,
+        parent_share_bps: royalty.parent_share_bps,
+    }))
+}
+
+// `factor ^ exp` by squaring, both in PRICE_PRECISION fixed point, none on overflow
+fn fixed_point_pow(mut factor: u128, mut exp: u64) -> Option<u128> {
+    let mul = |a: u128, b: u128| a.checked_mul(b).map(|product| product / PRICE_PRECISION);
+    let mut result = PRICE_PRECISION;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul(result, factor)?;
+        }
+        exp >>= 1;
+        if exp > 0 {
+            factor = mul(factor, factor)?;
+        }
+    }
+    Some(result)
+}
+
+// Breed price of a pairing priced at `base` once `minted` children exist
+fn current_price(config: &Config, base: Uint128, minted: u64) -> Result<Uint128, ContractError> {
+    match &config.pricing {
+        PricingMode::Static {} => Ok(base),
+        PricingMode::Linear { increment } => increment
+            .u128()
+            .checked_mul(minted as u128)
+            .and_then(|step| step.checked_add(base.u128()))
+            .map(Uint128::from)
+            .ok_or(ContractError::PriceOverflow {}),
+        PricingMode::Exponential { growth_bps } => {
+            // The growth factor is raised in fixed point, so only the final price is rounded down
+            let factor = (BPS_TOTAL as u128 + *growth_bps as u128) * PRICE_PRECISION / BPS_TOTAL as u128;
+            fixed_point_pow(factor, minted)
+                .and_then(|multiplier| base.u128().checked_mul(multiplier))
+                .map(|price| Uint128::from(price / PRICE_PRECISION))
+                .ok_or(ContractError::PriceOverflow {})
+        }
+        PricingMode::Stepped { tiers } => Ok(tiers
+            .iter()
+            .rev()
+            .find(|tier| tier.min_minted <= minted)
+            .map(|tier| tier.amount)
+            .unwrap_or(base)),
+    }
+}
+
+fn set_allowlist(
+    deps: DepsMut,
+    info: MessageInfo,
+    merkle_root: String,
+    discount_bps: u16,
+    quota: u32,
+) -> Result<Response, ContractError> {
+    let config = read_config(deps.storage)?;
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut root_buf: [u8; 32] = [0; 32];
+    hex::decode_to_slice(&merkle_root, &mut root_buf)
+        .map_err(|_| ContractError::InvalidHash {})?;
+
+    if discount_bps > BPS_TOTAL {
+        return Err(ContractError::InvalidDiscount {});
+    }
+
+    let id = read_allowlist(deps.storage)?.map(|round| round.id + 1).unwrap_or(1);
+    let round = AllowlistRound {
+        id,
+        merkle_root,
+        discount_bps,
+        quota,
+    };
+    save_allowlist(deps.storage, &round)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr(events::ACTION, events::ACTION_SET_ALLOWLIST),
+            attr(events::ALLOWLIST_ROUND, round.id),
+            attr(events::MERKLE_ROOT, &round.merkle_root),
+            attr(events::DISCOUNT_BPS, round.discount_bps),
+            attr(events::QUOTA, round.quota),
+        ],
+        ..Response::default()
+    })
+}
+
+fn close_allowlist(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = read_config(deps.storage)?;
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut attributes = vec![attr(events::ACTION, events::ACTION_CLOSE_ALLOWLIST)];
+
+    // Keep the round id so that the next round still gets a fresh one
+    if let Some(mut round) = read_allowlist(deps.storage)? {
+        round.quota = 0;
+        save_allowlist(deps.storage, &round)?;
+        attributes.push(attr(events::ALLOWLIST_ROUND, round.id));
+    }
+
+    Ok(Response {
+        attributes,
+        ..Response::default()
+    })
+}
+
+// Leaves are sha256(address) and each level hashes the sorted pair of nodes
+fn verify_allowlist_proof(merkle_root: &str, address: &str, proof: &[String]) -> Result<bool, ContractError> {
+    let leaf: [u8; 32] = Sha256::digest(address.as_bytes()).into();
+
+    let mut hash = leaf;
+    for node in proof {
+        let mut node_buf: [u8; 32] = [0; 32];
+        hex::decode_to_slice(node, &mut node_buf)
+            .map_err(|_| ContractError::InvalidHash {})?;
+        let mut pair = [hash, node_buf];
+        pair.sort_unstable();
+        hash = Sha256::digest(&pair.concat()).into();
+    }
+
+    let mut root_buf: [u8; 32] = [0; 32];
+    hex::decode_to_slice(merkle_root, &mut root_buf)
+        .map_err(|_| ContractError::InvalidHash {})?;
+    Ok(hash == root_buf)
+}
+
+fn add_parent_collection(deps: DepsMut, info: MessageInfo, addr: String) -> Result<Response, ContractError> {
+    let config = read_config(deps.storage)?;
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let collection = ParentCollection {
+        addr: deps.api.addr_canonicalize(&addr)?,
+    };
+    save_parent_collection(deps.storage, &collection)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr(events::ACTION, events::ACTION_ADD_PARENT_COLLECTION),
+            attr(events::COLLECTION, addr),
+        ],
+        ..Response::default()
+    })
+}
+
+fn remove_parent_collection(deps: DepsMut, info: MessageInfo, addr: String) -> Result<Response, ContractError> {
+    let config = read_config(deps.storage)?;
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let collection = deps.api.addr_canonicalize(&addr)?;
+    if read_parent_collection(deps.storage, &collection)?.is_none() {
+        return Err(ContractError::not_found("ParentCollection"));
+    }
+    let paired = read_pairings(deps.storage)?
+        .iter()
+        .any(|pairing| pairing.collection1 == collection || pairing.collection2 == collection);
+    if paired {
+        return Err(ContractError::CollectionInUse {});
+    }
+    remove_parent_collection_entry(deps.storage, &collection);
+
+    Ok(Response {
+        attributes: vec![
+            attr(events::ACTION, events::ACTION_REMOVE_PARENT_COLLECTION),
+            attr(events::COLLECTION, addr),
+        ],
+        ..Response::default()
+    })
+}
+
+fn add_hook(deps: DepsMut, info: MessageInfo, addr: String, events: Vec<HookEvent>) -> Result<Response, ContractError> {
+    let config = read_config(deps.storage)?;
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if events.is_empty() {
+        return Err(ContractError::InvalidHook {
+            reason: "no events".to_string(),
+        });
+    }
+    let mut unique_events: Vec<HookEvent> = vec![];
+    for event in events {
+        if unique_events.contains(&event) {
+            return Err(ContractError::InvalidHook {
+                reason: format!("duplicate event {}", event.as_str()),
+            });
+        }
+        unique_events.push(event);
+    }
+
+    // Updating the events of a registered hook does not count against the limit
+    let hook_addr = deps.api.addr_canonicalize(&addr)?;
+    if read_hook(deps.storage, &hook_addr)?.is_none() && read_hooks(deps.storage)?.len() as u32 >= MAX_HOOKS {
+        return Err(ContractError::LimitReached { limit: MAX_HOOKS });
+    }
+
+    let hook = Hook {
+        addr: hook_addr,
+        events: unique_events,
+    };
+    save_hook(deps.storage, &hook)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr(events::ACTION, events::ACTION_ADD_HOOK),
+            attr(events::HOOK, addr),
+            attr(events::HOOK_EVENTS, hook.events.iter().map(|event| event.as_str()).collect::<Vec<_>>().join(",")),
+        ],
+        ..Response::default()
+    })
+}
+
+fn remove_hook(deps: DepsMut, info: MessageInfo, addr: String) -> Result<Response, ContractError> {
+    let config = read_config(deps.storage)?;
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let hook_addr = deps.api.addr_canonicalize(&addr)?;
+    if read_hook(deps.storage, &hook_addr)?.is_none() {
+        return Err(ContractError::not_found("Hook"));
+    }
+    remove_hook_entry(deps.storage, &hook_addr);
+
+    Ok(Response {
+        attributes: vec![
+            attr(events::ACTION, events::ACTION_REMOVE_HOOK),
+            attr(events::HOOK, addr),
+        ],
+        ..Response::default()
+    })
+}
+
+// Submessages notifying every hook subscribed to the event of `msg`
+fn hook_submsgs(deps: Deps, msg: BreedingHookMsg) -> StdResult<Vec<SubMsg>> {
+    let event = msg.event();
+    let mut submessages = vec![];
+    for hook in read_hooks(deps.storage)? {
+        if hook.events.contains(&event) {
+            submessages.push(SubMsg {
+                id: HOOK_REPLY_ID,
+                msg: msg.clone().into_cosmos_msg(deps.api.addr_humanize(&hook.addr)?.to_string())?,
+                gas_limit: Some(HOOK_GAS_LIMIT),
+                reply_on: ReplyOn::Error,
+            });
+        }
+    }
+    Ok(submessages)
+}
+
+// A failed hook is rolled back on its own and only reported, the breeding action goes through
+fn reply_hook_failed(msg: Reply) -> Result<Response, ContractError> {
+    let reason = match msg.result.into_result() {
+        Ok(_) => {
+            return Err(ContractError::InvalidHookReply {
+                reason: "hook replies are only expected on error".to_string(),
+            })
+        }
+        Err(reason) => reason,
+    };
+
+    Ok(Response {
+        attributes: vec![
+            attr(events::ACTION, events::ACTION_HOOK_FAILED),
+            attr(events::ERROR, reason),
+        ],
+        ..Response::default()
+    })
+}
+
+fn set_pairing(
+    deps: DepsMut,
+    info: MessageInfo,
+    collection1: String,
+    collection2: String,
+    breed_price_amount: Option<Uint128>,
+    breed_duration: Option<u64>,
+    child_contract_addr: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = read_config(deps.storage)?;
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let collection1_addr = deps.api.addr_canonicalize(&collection1)?;
+    let collection2_addr = deps.api.addr_canonicalize(&collection2)?;
+    for collection in [&collection1_addr, &collection2_addr].iter() {
+        if read_parent_collection(deps.storage, collection)?.is_none() {
+            return Err(ContractError::UnregisteredCollection {});
+        }
+    }
+
+    let pairing = Pairing {
+        collection1: collection1_addr,
+        collection2: collection2_addr,
+        breed_price_amount: breed_price_amount.unwrap_or(config.config.breed_price_amount),
+        breed_duration: breed_duration.unwrap_or(config.config.breed_duration),
+        child_contract_addr: match child_contract_addr {
+            Some(addr) => Some(deps.api.addr_canonicalize(&addr)?),
+            None => None,
+        },
+    };
+    save_pairing(deps.storage, &pairing)?;
+
+    let mut attributes = vec![
+        attr(events::ACTION, events::ACTION_SET_PAIRING),
+        attr(events::COLLECTION1, collection1),
+        attr(events::COLLECTION2, collection2),
+        attr(events::BREED_PRICE_AMOUNT, pairing.breed_price_amount),
+        attr(events::BREED_DURATION, pairing.breed_duration),
+    ];
+    if let Some(child_contract_addr) = &pairing.child_contract_addr {
+        attributes.push(attr(events::CHILD_CONTRACT, deps.api.addr_humanize(child_contract_addr)?));
+    }
+
+    Ok(Response {
+        attributes,
+        ..Response::default()
+    })
+}
+
+fn remove_pairing(
+    deps: DepsMut,
+    info: MessageInfo,
+    collection1: String,
+    collection2: String,
+) -> Result<Response, ContractError> {
+    let config = read_config(deps.storage)?;
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let collection1_addr = deps.api.addr_canonicalize(&collection1)?;
+    let collection2_addr = deps.api.addr_canonicalize(&collection2)?;
+    if read_pairing(deps.storage, &collection1_addr, &collection2_addr)?.is_none() {
+        return Err(ContractError::not_found("Pairing"));
+    }
+    remove_pairing_entry(deps.storage, &collection1_addr, &collection2_addr);
+
+    Ok(Response {
+        attributes: vec![
+            attr(events::ACTION, events::ACTION_REMOVE_PAIRING),
+            attr(events::COLLECTION1, collection1),
+            attr(events::COLLECTION2, collection2),
+        ],
+        ..Response::default()
+    })
+}
+
+fn instantiate_child_collection(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection: ChildCollectionMsg,
+    pairing: Option<(String, String)>,
+) -> Result<Response, ContractError> {
+    let config = read_config(deps.storage)?;
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut attributes = vec![attr(events::ACTION, events::ACTION_INSTANTIATE_CHILD_COLLECTION)];
+    let target = match pairing {
+        Some((collection1, collection2)) => {
+            let collection1_addr = deps.api.addr_canonicalize(&collection1)?;
+            let collection2_addr = deps.api.addr_canonicalize(&collection2)?;
+            if read_pairing(deps.storage, &collection1_addr, &collection2_addr)?.is_none() {
+                return Err(ContractError::not_found("Pairing"));
+            }
+            attributes.push(attr(events::COLLECTION1, collection1));
+            attributes.push(attr(events::COLLECTION2, collection2));
+            ChildCollectionTarget::Pairing(collection1_addr, collection2_addr)
+        }
+        None => ChildCollectionTarget::Default,
+    };
+
+    Ok(Response {
+        submessages: vec![instantiate_child_collection_msg(deps, &env, target, collection)?],
+        attributes,
+        ..Response::default()
+    })
+}
+
+fn instantiate_receipt_collection(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection: ChildCollectionMsg,
+) -> Result<Response, ContractError> {
+    let config = read_config(deps.storage)?;
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    Ok(Response {
+        submessages: vec![instantiate_child_collection_msg(deps, &env, ChildCollectionTarget::Receipts, collection)?],
+        attributes: vec![attr(events::ACTION, events::ACTION_INSTANTIATE_RECEIPT_COLLECTION)],
+        ..Response::default()
+    })
+}
+
+// Instantiates a cw721 collection minted by this contract, its address is captured in the reply
+fn instantiate_child_collection_msg(
+    deps: DepsMut,
+    env: &Env,
+    target: ChildCollectionTarget,
+    collection: ChildCollectionMsg,
+) -> StdResult<SubMsg> {
+    save_pending_child_collection(deps.storage, &target)?;
+
+    let nft_instantiate_msg = NftInstantiateMsg {
+        name: collection.name,
+        symbol: collection.symbol,
+        minter: env.contract.address.to_string(),
+    };
+
+    Ok(SubMsg {
+        id: INSTANTIATE_CHILD_REPLY_ID,
+        msg: CosmosMsg::Wasm(WasmMsg::Instantiate {
+            admin: None,
+            code_id: collection.code_id,
+            msg: to_binary(&nft_instantiate_msg)?,
+            send: vec![],
+            label: collection.label,
+        }),
+        gas_limit: None,
+        reply_on: ReplyOn::Success,
+    })
+}
+
+fn reply_instantiate_child_collection(deps: DepsMut, msg: Reply) -> Result<Response, ContractError> {
+    let target = take_pending_child_collection(deps.storage)?;
+
+    let data = match msg.result.into_result().map_err(|reason| ContractError::InvalidReply { reason })?.data {
+        Some(data) => data,
+        None => {
+            return Err(ContractError::InvalidReply {
+                reason: "no data".to_string(),
+            })
+        }
+    };
+    let res = MsgInstantiateContractResponse::decode(data.as_slice())
+        .map_err(|_| ContractError::InvalidReply {
+            reason: "invalid MsgInstantiateContractResponse".to_string(),
+        })?;
+    let child_contract = res.contract_address;
+    let child_contract_addr = deps.api.addr_canonicalize(&child_contract)?;
+    let mut attributes = vec![
+        attr(events::ACTION, events::ACTION_CHILD_COLLECTION_INSTANTIATED),
+        attr(events::CHILD_CONTRACT, &child_contract),
+    ];
+
+    match target {
+        ChildCollectionTarget::Default => {
+            let mut config = read_config(deps.storage)?;
+            config.config.child_contract_addr = Some(child_contract_addr);
+            save_config(deps.storage, &config)?;
+        }
+        ChildCollectionTarget::Pairing(collection1, collection2) => {
+            let mut pairing = match read_pairing(deps.storage, &collection1, &collection2)? {
+                Some(pairing) => pairing,
+                None => return Err(ContractError::not_found("Pairing")),
+            };
+            pairing.child_contract_addr = Some(child_contract_addr);
+            save_pairing(deps.storage, &pairing)?;
+            attributes.push(attr(events::COLLECTION1, deps.api.addr_humanize(&pairing.collection1)?));
+            attributes.push(attr(events::COLLECTION2, deps.api.addr_humanize(&pairing.collection2)?));
+        }
+        // Breeds already started keep their receipt collection, only later ones use the new one
+        ChildCollectionTarget::Receipts => {
+            let mut config = read_config(deps.storage)?;
+            config.config.receipt_contract_addr = Some(child_contract_addr);
+            save_config(deps.storage, &config)?;
+            attributes.push(attr(events::RECEIPT_CONTRACT, &child_contract));
+        }
+    }
+
+    Ok(Response {
+        attributes,
+        ..Response::default()
+    })
+}
+
+// Records the info and the configured royalty of a child about to be minted. `parent_owner` is
+// only known for bred children.
+fn record_child(
+    storage: &mut dyn Storage,
+    config: &Config,
+    token_id: &str,
+    info: &ChildInfo,
+    parent_owner: Option<&CanonicalAddr>,
+) -> StdResult<()> {
+    save_child_info(storage, token_id, info)?;
+    if let Some(royalty) = &config.royalty {
+        let child_royalty = ChildRoyalty {
+            payment_address: royalty.payment_address.clone(),
+            percentage: royalty.percentage,
+            parent_owner: parent_owner.filter(|_| royalty.parent_share_bps > 0).cloned(),
+            parent_share_bps: royalty.parent_share_bps,
+        };
+        save_child_royalty(storage, token_id, &child_royalty)?;
+    }
+    Ok(())
+}
+
+// Builds the message minting a child token to `owner`, named after its token id without metadata
+fn mint_child_msg(
+    deps: Deps,
+    child_contract_addr: &CanonicalAddr,
+    token_id: String,
+    owner: &str,
+    metadata: Option<&Metadata>,
+) -> StdResult<CosmosMsg> {
+    let metadata = metadata.cloned().unwrap_or_default();
+    let nft_mint_msg = NftExecuteMsg::Mint(MintMsg {
+        name: metadata.name.unwrap_or_else(|| token_id.clone()),
+        description: metadata.description,
+        image: metadata.image,
+        token_id,
+        owner: owner.to_string(),
+    });
+
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: deps.api.addr_humanize(child_contract_addr)?.to_string(),
+        msg: to_binary(&nft_mint_msg)?,
+        send: vec![],
+    }))
+}
+
+// Builds the message moving a parent token out of `collection` to `recipient`
+fn transfer_nft_msg(deps: Deps, collection: &CanonicalAddr, token_id: &str, recipient: &str) -> StdResult<CosmosMsg> {
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: deps.api.addr_humanize(collection)?.to_string(),
+        msg: to_binary(&Cw721ExecuteMsg::TransferNft {
+            recipient: recipient.to_string(),
+            token_id: token_id.to_string(),
+        })?,
+        send: vec![],
+    }))
+}
+
+// Messages handing both escrowed parents of a breed to `recipient`
+fn return_parents_msgs(deps: Deps, breed: &Breed, recipient: &str) -> StdResult<Vec<CosmosMsg>> {
+    Ok(vec![
+        transfer_nft_msg(deps, &breed.nft_contract_addr1, &breed.nft_token_id1, recipient)?,
+        transfer_nft_msg(deps, &breed.nft_contract_addr2, &breed.nft_token_id2, recipient)?,
+    ])
+}
+
+// Other functions such as start_breed, breed, mint, withdraw, withdraw_fund, query_config, query_breed_info, query_breeded_count, query_breed_requests_count, query_breed_finished_count, query_breedings, query_breedings_length, query_user_breedings, and sort_breedings go here...
+
+
+fn breed(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    parent1: ParentNft,
+    parent2: ParentNft,
+    proof: Option<Vec<String>>,
+    auto_claim_tip: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let config = read_config(deps.storage)?;
+    if config.config.breed_price_cw20.is_some() {
+        return Err(ContractError::Cw20PaymentRequired {});
+    }
+
+    let denom = config.config.breed_price_denom.clone();
+    let paid = native_payment(&info.funds, &denom)?;
+    let owner = deps.api.addr_canonicalize(info.sender.as_str())?;
+
+    create_breed(deps, env, owner, parent1, parent2, proof, auto_claim_tip, FeeDenom::Native(denom), paid)
+}
+
+fn breed_batch(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection1: String,
+    collection2: String,
+    pairs: Vec<(String, String)>,
+    auto_claim_tip: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let config = read_config(deps.storage)?;
+    if config.config.breed_price_cw20.is_some() {
+        return Err(ContractError::Cw20PaymentRequired {});
+    }
+
+    let denom = config.config.breed_price_denom.clone();
+    let paid = native_payment(&info.funds, &denom)?;
+    let owner = deps.api.addr_canonicalize(info.sender.as_str())?;
+
+    create_breed_batch(deps, env, owner, collection1, collection2, pairs, auto_claim_tip, FeeDenom::Native(denom), paid)
+}
+
+// CW20 hook, the sending token contract is `info.sender` and the breeder is `wrapper.sender`
+fn receive_cw20(deps: DepsMut, env: Env, info: MessageInfo, wrapper: Cw20ReceiveMsg) -> Result<Response, ContractError> {
+    let config = read_config(deps.storage)?;
+    let token = deps.api.addr_canonicalize(info.sender.as_str())?;
+    if config.config.breed_price_cw20 != Some(token.clone()) {
+        return Err(ContractError::InvalidCw20Token {});
+    }
+
+    let owner = deps.api.addr_canonicalize(&wrapper.sender)?;
+    match from_binary(&wrapper.msg)? {
+        ReceiveMsg::Breed { parent1, parent2, proof, auto_claim_tip } => {
+            create_breed(deps, env, owner, parent1, parent2, proof, auto_claim_tip, FeeDenom::Cw20(token), wrapper.amount)
+        }
+        ReceiveMsg::BreedBatch { collection1, collection2, pairs, auto_claim_tip } => {
+            create_breed_batch(deps, env, owner, collection1, collection2, pairs, auto_claim_tip, FeeDenom::Cw20(token), wrapper.amount)
+        }
+    }
+}
+
+// CW721 hook, the receipt collection is `info.sender` and the receipt holder is `wrapper.sender`.
+// The receipt is already owned by this contract when the breed is settled, and it stays here.
+fn receive_nft(deps: DepsMut, env: Env, info: MessageInfo, wrapper: Cw721ReceiveMsg) -> Result<Response, ContractError> {
+    let msg: ReceiptMsg = match &wrapper.msg {
+        Some(msg) => from_binary(msg)?,
+        None => return Err(ContractError::InvalidReceipt {}),
+    };
+    let breed_id: u64 = wrapper.token_id.parse().map_err(|_| ContractError::InvalidReceipt {})?;
+    let collection = Some(deps.api.addr_canonicalize(info.sender.as_str())?);
+    let holder = deps.api.addr_validate(&wrapper.sender)?;
+
+    // A rescued breed only waits for its receipt to hand back the parents
+    if let Some(breed) = read_rescued_breed(deps.storage, breed_id)? {
+        if breed.receipt_contract_addr != collection {
+            return Err(ContractError::InvalidReceipt {});
+        }
+        return redeem_rescued_breed(deps, breed, holder);
+    }
+
+    let breed = match read_breed(deps.storage, breed_id)? {
+        Some(breed) => breed,
+        None => return Err(ContractError::not_found("Breed")),
+    };
+    if breed.receipt_contract_addr != collection {
+        return Err(ContractError::InvalidReceipt {});
+    }
+    match msg {
+        ReceiptMsg::Withdraw {} => {
+            let holder = deps.api.addr_canonicalize(holder.as_str())?;
+            let (messages, submessages, attributes) = withdraw_breed(deps, &env, &holder, breed_id, Withdrawer::ReceiptHolder)?;
+            Ok(Response {
+                submessages,
+                messages,
+                attributes,
+                ..Response::default()
+            })
+        }
+        ReceiptMsg::Cancel {} => cancel_unsettled_breed(deps, &env, breed, holder),
+    }
+}
+
+// Hands the escrowed parents of a rescued receipt breed to whoever sent its receipt
+fn redeem_rescued_breed(deps: DepsMut, breed: Breed, holder: Addr) -> Result<Response, ContractError> {
+    remove_rescued_breed(deps.storage, breed.id);
+    let messages = return_parents_msgs(deps.as_ref(), &breed, holder.as_str())?;
+    let mut attributes = breed_attributes(deps.as_ref(), events::ACTION_REDEEM_RECEIPT, &breed)?;
+    attributes.push(attr(events::RECIPIENT, holder));
+
+    Ok(Response {
+        messages,
+        attributes,
+        ..Response::default()
+    })
+}
+
+// Sums the funds sent in `denom`, rejecting any other denom
+fn native_payment(funds: &[Coin], denom: &str) -> Result<Uint128, ContractError> {
+    let mut paid = Uint128::zero();
+    for coin in funds {
+        if coin.denom != denom {
+            return Err(ContractError::UnexpectedFunds {
+                denom: coin.denom.clone(),
+            });
+        }
+        paid += coin.amount;
+    }
+    Ok(paid)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn create_breed(
+    deps: DepsMut,
+    env: Env,
+    owner: CanonicalAddr,
+    parent1: ParentNft,
+    parent2: ParentNft,
+    proof: Option<Vec<String>>,
+    auto_claim_tip: Option<Uint128>,
+    fee_denom: FeeDenom,
+    fee_amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = read_config(deps.storage)?;
+    if config.config.paused {
+        return Err(ContractError::Paused {});
+    }
+
+    let breed_count = read_breed_count(deps.storage)?;
+    if breed_count.count >= config.config.breed_count_limit as u64 {
+        return Err(ContractError::LimitReached {
+            limit: config.config.breed_count_limit,
+        });
+    }
+
+    let mut new_breed = prepare_breed(deps.as_ref(), &env, &config.config, &breed_count, &owner, breed_count.latest_id + 1, parent1, parent2, auto_claim_tip, &fee_denom)?;
+
+    // A valid allowlist proof consumes one unit of quota and discounts the price
+    if let Some(proof) = proof {
+        let round = match read_allowlist(deps.storage)? {
+            Some(round) => round,
+            None => return Err(ContractError::NoAllowlist {}),
+        };
+        if !verify_allowlist_proof(&round.merkle_root, deps.api.addr_humanize(&owner)?.as_str(), &proof)? {
+            return Err(ContractError::InvalidProof {});
+        }
+        let used = read_allowlist_usage(deps.storage, round.id, &owner)?;
+        if used >= round.quota {
+            return Err(ContractError::QuotaExceeded {});
+        }
+        save_allowlist_usage(deps.storage, round.id, &owner, used + 1)?;
+        new_breed.allowlist_round = Some(round.id);
+        new_breed.fee_amount = new_breed.fee_amount.multiply_ratio(BPS_TOTAL - round.discount_bps, BPS_TOTAL);
+    }
+
+    // An auto-claim tip is paid on top of the breed price
+    let expected = new_breed.fee_amount + auto_claim_tip.unwrap_or_default();
+    if fee_amount != expected {
+        return Err(ContractError::WrongFunds {
+            expected,
+            received: fee_amount,
+        });
+    }
+
+    let (messages, submessages, attributes) = start_breeds(deps, &env, breed_count, vec![new_breed])?;
+
+    Ok(Response {
+        submessages,
+        messages,
+        attributes,
+        ..Response::default()
+    })
+}
+
+// Breeds every token id pair of one pairing, the batch is paid with the sum of the breed prices
+// and tips and fails as a whole if any pair is invalid
+#[allow(clippy::too_many_arguments)]
+fn create_breed_batch(
+    deps: DepsMut,
+    env: Env,
+    owner: CanonicalAddr,
+    collection1: String,
+    collection2: String,
+    pairs: Vec<(String, String)>,
+    auto_claim_tip: Option<Uint128>,
+    fee_denom: FeeDenom,
+    fee_amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = read_config(deps.storage)?;
+    if config.config.paused {
+        return Err(ContractError::Paused {});
+    }
+
+    if pairs.is_empty() {
+        return Err(ContractError::EmptyBatch {});
+    }
+    if pairs.len() > config.config.max_batch_size as usize {
+        return Err(ContractError::BatchTooLarge {
+            max: config.config.max_batch_size,
+        });
+    }
+
+    let breed_count = read_breed_count(deps.storage)?;
+    if breed_count.count + pairs.len() as u64 > config.config.breed_count_limit as u64 {
+        return Err(ContractError::LimitReached {
+            limit: config.config.breed_count_limit,
+        });
+    }
+
+    // A parent is escrowed by its breed, so it can only appear once in the batch
+    let mut parents: Vec<(&String, &String)> = vec![];
+    for (token_id1, token_id2) in pairs.iter() {
+        for parent in [(&collection1, token_id1), (&collection2, token_id2)].iter() {
+            if parents.contains(parent) {
+                return Err(ContractError::DuplicateParent {
+                    token_id: parent.1.clone(),
+                });
+            }
+            parents.push(*parent);
+        }
+    }
+
+    let mut new_breeds = vec![];
+    let mut total = Uint128::zero();
+    for (index, (token_id1, token_id2)) in pairs.iter().enumerate() {
+        let parent1 = ParentNft {
+            collection: collection1.clone(),
+            token_id: token_id1.clone(),
+        };
+        let parent2 = ParentNft {
+            collection: collection2.clone(),
+            token_id: token_id2.clone(),
+        };
+        let breed_id = breed_count.latest_id + 1 + index as u64;
+        let new_breed = prepare_breed(deps.as_ref(), &env, &config.config, &breed_count, &owner, breed_id, parent1, parent2, auto_claim_tip, &fee_denom)?;
+        // The auto-claim tip applies to every breed of the batch
+        total += new_breed.fee_amount + auto_claim_tip.unwrap_or_default();
+        new_breeds.push(new_breed);
+    }
+
+    if fee_amount != total {
+        return Err(ContractError::WrongFunds {
+            expected: total,
+            received: fee_amount,
+        });
+    }
+
+    let breed_ids = new_breeds.iter().map(|breed| breed.id).collect();
+    let (messages, submessages, attributes) = start_breeds(deps, &env, breed_count, new_breeds)?;
+
+    Ok(Response {
+        submessages,
+        messages,
+        attributes,
+        data: Some(to_binary(&BreedBatchResponse { breed_ids })?),
+    })
+}
+
+// Validates a pair of parents and builds its breed, priced without any allowlist discount
+#[allow(clippy::too_many_arguments)]
+fn prepare_breed(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    breed_count: &BreedCount,
+    owner: &CanonicalAddr,
+    breed_id: u64,
+    parent1: ParentNft,
+    parent2: ParentNft,
+    auto_claim_tip: Option<Uint128>,
+    fee_denom: &FeeDenom,
+) -> Result<Breed, ContractError> {
+    let collection1 = deps.api.addr_canonicalize(&parent1.collection)?;
+    let collection2 = deps.api.addr_canonicalize(&parent2.collection)?;
+    if collection1 == collection2 && parent1.token_id == parent2.token_id {
+        return Err(ContractError::SelfBreeding {});
+    }
+
+    // A receipt breed is settled by sending its receipt, which a keeper cannot do
+    if auto_claim_tip.is_some() && config.receipt_contract_addr.is_some() {
+        return Err(ContractError::ReceiptAutoClaim {});
+    }
+
+    let pairing = match read_pairing(deps.storage, &collection1, &collection2)? {
+        Some(pairing) => pairing,
+        None => return Err(ContractError::NotPaired {}),
+    };
+
+    // The child collection is resolved now so that later changes do not affect this breed
+    let child_contract_addr = match pairing.child_contract_addr.clone().or_else(|| config.child_contract_addr.clone()) {
+        Some(addr) => addr,
+        None => return Err(ContractError::NoChildCollection {}),
+    };
+
+    // Both parents must belong to the breeder, they are escrowed until the breed ends
+    let owner_addr = deps.api.addr_humanize(owner)?;
+    for parent in [&parent1, &parent2].iter() {
+        let res: OwnerOfResponse = deps.querier.query_wasm_smart(
+            parent.collection.clone(),
+            &Cw721QueryMsg::OwnerOf {
+                token_id: parent.token_id.clone(),
+                include_expired: None,
+            },
+        )?;
+        if res.owner != owner_addr.as_str() {
+            return Err(ContractError::NotParentOwner {
+                token_id: parent.token_id.clone(),
+            });
+        }
+    }
+
+    // The price follows the pricing mode at the moment the breed starts
+    let price = current_price(config, pairing.breed_price_amount, breed_count.minted)?;
+
+    Ok(Breed {
+        id: breed_id,
+        start_time: env.block.time.seconds(),
+        owner: owner.clone(),
+        nft_contract_addr1: collection1,
+        nft_token_id1: parent1.token_id,
+        nft_contract_addr2: collection2,
+        nft_token_id2: parent2.token_id,
+        child_contract_addr,
+        end_time: env.block.time.seconds() + pairing.breed_duration,
+        is_withdrawn: false,
+        fee_denom: fee_denom.clone(),
+        fee_amount: price,
+        auto_claim_tip,
+        receipt_contract_addr: config.receipt_contract_addr.clone(),
+        child_token_id: None,
+        breeder: Some(owner.clone()),
+        allowlist_round: None,
+    })
+}
+
+// Saves validated breeds, escrows their fees and pulls their parents into the contract
+fn start_breeds(
+    deps: DepsMut,
+    env: &Env,
+    breed_count: BreedCount,
+    new_breeds: Vec<Breed>,
+) -> Result<BreedMessages, ContractError> {
+    let mut messages = vec![];
+    let mut submessages = vec![];
+    let mut attributes = vec![];
+    let mut updated_breed_count = breed_count;
+    for new_breed in new_breeds {
+        save_breed(deps.storage, new_breed.id, &new_breed)?;
+        save_owner_breed(deps.storage, &new_breed.owner, new_breed.id)?;
+        if new_breed.auto_claim_tip.is_some() {
+            save_auto_claim_breed(deps.storage, new_breed.id)?;
+        }
+        updated_breed_count.count += 1;
+        updated_breed_count.latest_id = new_breed.id;
+        record_stats(deps.storage, env, &updated_breed_count, &new_breed.owner, 1, 0)?;
+        let spenders = LeaderboardKind::Spenders(new_breed.fee_denom.clone());
+        update_leaderboard(deps.storage, &spenders, &new_breed.owner, None, |spent| Ok(spent + new_breed.fee_amount))?;
+
+        // The fee stays refundable until the breed is withdrawn, the tip waits for whoever claims it
+        let mut ledger = read_fee_ledger(deps.storage, &new_breed.fee_denom)?;
+        ledger.escrowed += new_breed.fee_amount;
+        if let Some(tip) = new_breed.auto_claim_tip {
+            ledger.tips += tip;
+        }
+        save_fee_ledger(deps.storage, &ledger)?;
+
+        // Pull both parents into escrow, this requires the breeder's approval on each token
+        messages.push(transfer_nft_msg(deps.as_ref(), &new_breed.nft_contract_addr1, &new_breed.nft_token_id1, env.contract.address.as_str())?);
+        messages.push(transfer_nft_msg(deps.as_ref(), &new_breed.nft_contract_addr2, &new_breed.nft_token_id2, env.contract.address.as_str())?);
+
+        // The receipt stands for the breed, whoever holds it withdraws the child and parents
+        if let Some(receipt_contract_addr) = &new_breed.receipt_contract_addr {
+            let owner = deps.api.addr_humanize(&new_breed.owner)?;
+            messages.push(mint_child_msg(deps.as_ref(), receipt_contract_addr, new_breed.id.to_string(), owner.as_str(), None)?);
+        }
+
+        // A batch reports its action once, followed by one block per breed starting at its breed_id
+        let mut new_breed_attributes = breed_attributes(deps.as_ref(), events::ACTION_BREED, &new_breed)?;
+        if !attributes.is_empty() {
+            new_breed_attributes.remove(0);
+        }
+        attributes.extend(new_breed_attributes);
+        attributes.push(attr(events::AMOUNT, new_breed.fee_amount));
+        attributes.push(attr(events::DENOM, fee_denom_label(deps.as_ref(), &new_breed.fee_denom)?));
+        attributes.push(attr(events::START_TIME, new_breed.start_time));
+        attributes.push(attr(events::END_TIME, new_breed.end_time));
+        if let Some(tip) = new_breed.auto_claim_tip {
+            attributes.push(attr(events::TIP, tip));
+        }
+        if let Some(receipt_contract_addr) = &new_breed.receipt_contract_addr {
+            attributes.push(attr(events::RECEIPT_CONTRACT, deps.api.addr_humanize(receipt_contract_addr)?));
+        }
+
+        submessages.extend(hook_submsgs(
+            deps.as_ref(),
+            BreedingHookMsg::BreedStarted {
+                breed_id: new_breed.id,
+                owner: deps.api.addr_humanize(&new_breed.owner)?.to_string(),
+                parent1: ParentNft {
+                    collection: deps.api.addr_humanize(&new_breed.nft_contract_addr1)?.to_string(),
+                    token_id: new_breed.nft_token_id1.clone(),
+                },
+                parent2: ParentNft {
+                    collection: deps.api.addr_humanize(&new_breed.nft_contract_addr2)?.to_string(),
+                    token_id: new_breed.nft_token_id2.clone(),
+                },
+                end_time: new_breed.end_time,
+            },
+        )?);
+    }
+    save_breed_count(deps.storage, &updated_breed_count)?;
+
+    Ok((messages, submessages, attributes))
+}
+
+// Address that paid the fee and tip of a breed, breeds started before it was recorded never changed hands
+fn breed_payer(breed: &Breed) -> &CanonicalAddr {
+    breed.breeder.as_ref().unwrap_or(&breed.owner)
+}
+
+// Removes a breed that did not go through with its indexes and pending offer, gives back its slot in
+// the breed count and its allowlist quota, and releases its fee and tip to be refunded to the payer
+fn remove_unsettled_breed(storage: &mut dyn Storage, env: &Env, breed: &Breed) -> Result<(Uint128, Uint128), ContractError> {
+    remove_breed(storage, breed.id)?;
+    remove_owner_breed(storage, &breed.owner, breed.id);
+    remove_auto_claim_breed(storage, breed.id);
+    remove_breed_offer(storage, breed.id);
+
+    let mut breed_count = read_breed_count(storage)?;
+    breed_count.count = breed_count.count.saturating_sub(1);
+    save_breed_count(storage, &breed_count)?;
+    let totals = BreedStats {
+        breeds: breed_count.count,
+        children: breed_count.minted,
+    };
+    save_stats(storage, None, env.block.height, &totals)?;
+
+    // The breed no longer counts for the breeder who started it
+    let payer = breed_payer(breed);
+    let mut stats = read_stats(storage, Some(payer), None)?;
+    stats.breeds = stats.breeds.saturating_sub(1);
+    save_stats(storage, Some(payer), env.block.height, &stats)?;
+
+    if let Some(round_id) = breed.allowlist_round {
+        let used = read_allowlist_usage(storage, round_id, payer)?;
+        save_allowlist_usage(storage, round_id, payer, used.saturating_sub(1))?;
+    }
+
+    let mut ledger = read_fee_ledger(storage, &breed.fee_denom)?;
+    ledger.escrowed = ledger.escrowed.checked_sub(breed.fee_amount)?;
+    let tip = breed.auto_claim_tip.unwrap_or_default();
+    ledger.tips = ledger.tips.checked_sub(tip)?;
+    save_fee_ledger(storage, &ledger)?;
+
+    // A refunded fee no longer counts as spent
+    let spenders = LeaderboardKind::Spenders(breed.fee_denom.clone());
+    update_leaderboard(storage, &spenders, payer, None, |spent| Ok(spent.checked_sub(breed.fee_amount).unwrap_or_default()))?;
+    Ok((breed.fee_amount, tip))
+}
+
+fn cancel_breed(deps: DepsMut, env: Env, info: MessageInfo, breed_id: u64) -> Result<Response, ContractError> {
+    let breed = match read_breed(deps.storage, breed_id)? {
+        Some(breed) => breed,
+        None => return Err(ContractError::not_found("Breed")),
+    };
+
+    if breed.receipt_contract_addr.is_some() {
+        return Err(ContractError::ReceiptRequired { breed_id });
+    }
+
+    if deps.api.addr_canonicalize(info.sender.as_str())? != breed.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    cancel_unsettled_breed(deps, &env, breed, info.sender)
+}
+
+// Cancels an incubating breed for its owner or receipt holder, who gets the parents back
+fn cancel_unsettled_breed(deps: DepsMut, env: &Env, breed: Breed, holder: Addr) -> Result<Response, ContractError> {
+    if env.block.time.seconds() >= breed.end_time {
+        return Err(ContractError::BreedFinished { breed_id: breed.id });
+    }
+
+    let (fee, tip) = remove_unsettled_breed(deps.storage, env, &breed)?;
+
+    // The parents go to the holder, the fee and tip back to whoever paid them
+    let payer = deps.api.addr_humanize(breed_payer(&breed))?;
+    let mut messages = return_parents_msgs(deps.as_ref(), &breed, holder.as_str())?;
+    if !(fee + tip).is_zero() {
+        messages.push(fee_transfer_msg(deps.as_ref(), &breed.fee_denom, fee + tip, payer.to_string())?);
+    }
+
+    let mut attributes = breed_attributes(deps.as_ref(), events::ACTION_CANCEL_BREED, &breed)?;
+    attributes.push(attr(events::REFUND_RECIPIENT, payer));
+    attributes.push(attr(events::AMOUNT, fee));
+    attributes.push(attr(events::DENOM, fee_denom_label(deps.as_ref(), &breed.fee_denom)?));
+    if !tip.is_zero() {
+        attributes.push(attr(events::TIP, tip));
+    }
+
+    let submessages = hook_submsgs(
+        deps.as_ref(),
+        BreedingHookMsg::BreedCancelled {
+            breed_id: breed.id,
+            owner: holder.to_string(),
+        },
+    )?;
+
+    Ok(Response {
+        submessages,
+        messages,
+        attributes,
+        ..Response::default()
+    })
+}
+
+// Loads a breed whose position `owner` may hand over, only breeds without a receipt that are not
+// settled or waiting for a keeper can change hands
+fn transferable_breed(deps: Deps, env: &Env, owner: &CanonicalAddr, breed_id: u64) -> Result<Breed, ContractError> {
+    let breed = match read_breed(deps.storage, breed_id)? {
+        Some(breed) => breed,
+        None => return Err(ContractError::not_found("Breed")),
+    };
+
+    if breed.receipt_contract_addr.is_some() {
+        return Err(ContractError::ReceiptBreed { breed_id });
+    }
+
+    if *owner != breed.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if breed.is_withdrawn {
+        return Err(ContractError::AlreadyWithdrawn { breed_id });
+    }
+
+    if breed.auto_claim_tip.is_some() && env.block.time.seconds() >= breed.end_time {
+        return Err(ContractError::BreedBeingClaimed { breed_id });
+    }
+
+    Ok(breed)
+}
+
+// Moves a breed to `new_owner`, dropping any pending offer made by the previous owner
+fn change_breed_owner(deps: DepsMut, mut breed: Breed, new_owner: CanonicalAddr, action: &str) -> StdResult<Vec<Attribute>> {
+    let previous_owner = deps.api.addr_humanize(&breed.owner)?;
+    remove_owner_breed(deps.storage, &breed.owner, breed.id);
+    save_owner_breed(deps.storage, &new_owner, breed.id)?;
+    breed.owner = new_owner;
+    save_breed(deps.storage, breed.id, &breed)?;
+    remove_breed_offer(deps.storage, breed.id);
+
+    let mut attributes = breed_attributes(deps.as_ref(), action, &breed)?;
+    attributes.push(attr(events::PREVIOUS_OWNER, previous_owner));
+    Ok(attributes)
+}
+
+fn transfer_breed(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    breed_id: u64,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    let sender_address = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let breed = transferable_breed(deps.as_ref(), &env, &sender_address, breed_id)?;
+    let recipient_address = deps.api.addr_canonicalize(&recipient)?;
+
+    Ok(Response {
+        attributes: change_breed_owner(deps, breed, recipient_address, events::ACTION_TRANSFER_BREED)?,
+        ..Response::default()
+    })
+}
+
+fn offer_breed(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    breed_id: u64,
+    recipient: String,
+    price: Option<Coin>,
+) -> Result<Response, ContractError> {
+    let sender_address = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let breed = transferable_breed(deps.as_ref(), &env, &sender_address, breed_id)?;
+
+    let offer = BreedOffer {
+        breed_id,
+        owner: breed.owner,
+        recipient: deps.api.addr_canonicalize(&recipient)?,
+        price,
+    };
+    save_breed_offer(deps.storage, &offer)?;
+
+    let mut attributes = vec![
+        attr(events::ACTION, events::ACTION_OFFER_BREED),
+        attr(events::BREED_ID, breed_id),
+        attr(events::RECIPIENT, recipient),
+    ];
+    if let Some(price) = &offer.price {
+        attributes.push(attr(events::AMOUNT, price.amount));
+        attributes.push(attr(events::DENOM, &price.denom));
+    }
+
+    Ok(Response {
+        attributes,
+        ..Response::default()
+    })
+}
+
+// Takes over an offered breed, the price is forwarded to the owner who made the offer
+fn accept_breed(deps: DepsMut, env: Env, info: MessageInfo, breed_id: u64) -> Result<Response, ContractError> {
+    let offer = match read_breed_offer(deps.storage, breed_id)? {
+        Some(offer) => offer,
+        None => return Err(ContractError::not_found("Breed offer")),
+    };
+
+    let sender_address = deps.api.addr_canonicalize(info.sender.as_str())?;
+    if sender_address != offer.recipient {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // The offer only holds while its maker still owns the breed
+    let breed = transferable_breed(deps.as_ref(), &env, &offer.owner, breed_id)?;
+
+    let mut messages = vec![];
+    match &offer.price {
+        Some(price) => {
+            let paid = native_payment(&info.funds, &price.denom)?;
+            if paid != price.amount {
+                return Err(ContractError::WrongFunds {
+                    expected: price.amount,
+                    received: paid,
+                });
+            }
+            if !price.amount.is_zero() {
+                messages.push(CosmosMsg::Bank(BankMsg::Send {
+                    to_address: deps.api.addr_humanize(&offer.owner)?.to_string(),
+                    amount: vec![price.clone()],
+                }));
+            }
+        }
+        None => {
+            if let Some(coin) = info.funds.first() {
+                return Err(ContractError::UnexpectedFunds {
+                    denom: coin.denom.clone(),
+                });
+            }
+        }
+    }
+
+    let mut attributes = change_breed_owner(deps, breed, sender_address, events::ACTION_ACCEPT_BREED)?;
+    if let Some(price) = &offer.price {
+        attributes.push(attr(events::AMOUNT, price.amount));
+        attributes.push(attr(events::DENOM, &price.denom));
+    }
+
+    Ok(Response {
+        messages,
+        attributes,
+        ..Response::default()
+    })
+}
+
+fn mint(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    extension: Option<Metadata>,
+) -> Result<Response, ContractError> {
+    // Children are minted on the default collection captured when it was instantiated
+    let config = read_config(deps.storage)?;
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if config.config.paused {
+        return Err(ContractError::Paused {});
+    }
+    if let Some(metadata) = &extension {
+        validate_metadata(metadata)?;
+    }
+
+    let child_contract_addr = match config.config.child_contract_addr.clone() {
+        Some(addr) => addr,
+        None => return Err(ContractError::NoChildCollection {}),
+    };
+
+    // Direct mints take the next breed id, so their token ids never collide with a bred child. The
+    // child counts like a withdrawn one for pricing, stats and the breeders leaderboard.
+    let owner = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut breed_count = read_breed_count(deps.storage)?;
+    breed_count.latest_id += 1;
+    breed_count.minted += 1;
+    breed_count.direct_minted += 1;
+    save_breed_count(deps.storage, &breed_count)?;
+    record_stats(deps.storage, &env, &breed_count, &owner, 0, 1)?;
+    update_leaderboard(deps.storage, &LeaderboardKind::Breeders, &owner, None, |completed| Ok(completed + Uint128::from(1u128)))?;
+    let token_id = child_token_id(&config.config.child_token_id_scheme, breed_count.latest_id, None);
+    let token_uri = child_token_uri(&config.config.child_base_uri, &config.config.child_uri_template, &token_id);
+
+    let mut attributes = vec![
+        attr(events::ACTION, events::ACTION_MINT),
+        attr(events::OWNER, &info.sender),
+        attr(events::CHILD_CONTRACT, deps.api.addr_humanize(&child_contract_addr)?),
+        attr(events::CHILD_TOKEN_ID, &token_id),
+    ];
+    if let Some(token_uri) = &token_uri {
+        attributes.push(attr(events::CHILD_TOKEN_URI, token_uri));
+    }
+
+    let messages = vec![mint_child_msg(deps.as_ref(), &child_contract_addr, token_id.clone(), info.sender.as_str(), extension.as_ref())?];
+    let child_info = ChildInfo {
+        token_uri,
+        metadata: extension,
+    };
+    record_child(deps.storage, &config.config, &token_id, &child_info, None)?;
+    let submessages = hook_submsgs(
+        deps.as_ref(),
+        BreedingHookMsg::ChildMinted {
+            breed_id: None,
+            child_contract_addr: deps.api.addr_humanize(&child_contract_addr)?.to_string(),
+            token_id,
+            owner: info.sender.to_string(),
+        },
+    )?;
+
+    Ok(Response {
+        submessages,
+        messages,
+        attributes,
+        ..Response::default()
+    })
+}
+
+// Forwards a royalty paid for a child, the parent share of every sent coin goes to the owner of
+// its parents and the rest to the royalty payment address
+fn pay_royalty(deps: DepsMut, info: MessageInfo, token_id: String) -> Result<Response, ContractError> {
+    let royalty = match read_child_royalty(deps.storage, &token_id)? {
+        Some(royalty) => royalty,
+        None => return Err(ContractError::not_found("Royalty")),
+    };
+    if info.funds.iter().all(|coin| coin.amount.is_zero()) {
+        return Err(ContractError::NoRoyaltyFunds {});
+    }
+
+    let parent_share_bps = if royalty.parent_owner.is_some() { royalty.parent_share_bps } else { 0 };
+    let mut payment = vec![];
+    let mut parent_payment = vec![];
+    for coin in info.funds.into_iter().filter(|coin| !coin.amount.is_zero()) {
+        let parent_amount = coin.amount.multiply_ratio(parent_share_bps, BPS_TOTAL);
+        let amount = coin.amount.checked_sub(parent_amount)?;
+        if !parent_amount.is_zero() {
+            parent_payment.push(Coin {
+                denom: coin.denom.clone(),
+                amount: parent_amount,
+            });
+        }
+        if !amount.is_zero() {
+            payment.push(Coin { denom: coin.denom, amount });
+        }
+    }
+
+    let payment_address = deps.api.addr_humanize(&royalty.payment_address)?;
+    let label = |coins: &[Coin]| {
+        coins
+            .iter()
+            .map(|coin| format!("{}{}", coin.amount, coin.denom))
+            .collect::<Vec<_>>()
+            .join(",")
+    };
+    let mut attributes = vec![
+        attr(events::ACTION, events::ACTION_PAY_ROYALTY),
+        attr(events::CHILD_TOKEN_ID, &token_id),
+        attr(events::RECIPIENT, &payment_address),
+        attr(events::AMOUNT, label(&payment)),
+    ];
+    let mut messages = vec![];
+    if !payment.is_empty() {
+        messages.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: payment_address.to_string(),
+            amount: payment,
+        }));
+    }
+    if let Some(parent_owner) = royalty.parent_owner.filter(|_| !parent_payment.is_empty()) {
+        let parent_owner = deps.api.addr_humanize(&parent_owner)?;
+        attributes.push(attr(events::PARENT_OWNER, &parent_owner));
+        attributes.push(attr(events::PARENT_AMOUNT, label(&parent_payment)));
+        messages.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: parent_owner.to_string(),
+            amount: parent_payment,
+        }));
+    }
+
+    Ok(Response {
+        messages,
+        attributes,
+        ..Response::default()
+    })
+}
+
+fn withdraw(deps: DepsMut, env: Env, info: MessageInfo, breed_id: u64) -> Result<Response, ContractError> {
+    let sender_address = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let (messages, submessages, attributes) = withdraw_breed(deps, &env, &sender_address, breed_id, Withdrawer::Owner)?;
+
+    Ok(Response {
+        submessages,
+        messages,
+        attributes,
+        ..Response::default()
+    })
+}
+
+// Lets anyone withdraw a finished breed that opted into auto-claim, for its tip
+fn claim_for(deps: DepsMut, env: Env, info: MessageInfo, breed_id: u64) -> Result<Response, ContractError> {
+    let sender_address = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let (messages, submessages, attributes) = withdraw_breed(deps, &env, &sender_address, breed_id, Withdrawer::Keeper)?;
+
+    Ok(Response {
+        submessages,
+        messages,
+        attributes,
+        ..Response::default()
+    })
+}
+
+// Withdraws the caller's finished breeds in id order after `start_after`, at most `limit` of them.
+// Only the caller's unsettled breeds are walked, the search stops at the first breed past the limit.
+fn withdraw_all(mut deps: DepsMut, env: Env, info: MessageInfo, start_after: Option<u64>, limit: u32) -> Result<Response, ContractError> {
+    let sender_address = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let start = start_after.map(|breed_id| (breed_id + 1).to_be_bytes());
+    let mut withdrawable = vec![];
+    for item in owner_breeds_read(deps.storage, &sender_address).range(start.as_ref().map(|start| start.as_ref()), None, Order::Ascending) {
+        if withdrawable.len() > limit as usize {
+            break;
+        }
+        let breed = match read_breed(deps.storage, item?.1)? {
+            Some(breed) => breed,
+            None => continue,
+        };
+        // Breeds held through a receipt are settled by sending the receipt
+        if breed.receipt_contract_addr.is_none() && env.block.time.seconds() >= breed.end_time {
+            withdrawable.push(breed.id);
+        }
+    }
+    let mut withdrawable = withdrawable.into_iter();
+
+    let mut breed_ids = vec![];
+    let mut messages = vec![];
+    let mut submessages = vec![];
+    let mut attributes = vec![attr(events::ACTION, events::ACTION_WITHDRAW)];
+    for breed_id in withdrawable.by_ref().take(limit as usize) {
+        let (breed_messages, breed_submessages, mut breed_attributes) = withdraw_breed(deps.branch(), &env, &sender_address, breed_id, Withdrawer::Owner)?;
+        messages.extend(breed_messages);
+        submessages.extend(breed_submessages);
+        // The withdraw action is reported once, even when nothing was withdrawable
+        breed_attributes.remove(0);
+        attributes.extend(breed_attributes);
+        breed_ids.push(breed_id);
+    }
+    let has_more = withdrawable.next().is_some();
+
+    Ok(Response {
+        submessages,
+        messages,
+        attributes,
+        data: Some(to_binary(&WithdrawAllResponse { breed_ids, has_more })?),
+    })
+}
+
+// Who withdraws a breed through withdraw_breed
+#[derive(Clone, Copy, PartialEq)]
+enum Withdrawer {
+    // The owner of a breed without a receipt
+    Owner,
+    // Any address claiming a finished auto-claim breed for its owner
+    Keeper,
+    // The holder who sent the receipt of the breed to this contract
+    ReceiptHolder,
+}
+
+// Withdraws one breed for its holder, shared by every withdraw path so their checks stay identical.
+// A keeper claim may come from any address when the breed opted into auto-claim. Either way the
+// tip goes to the sender, which refunds it when the owner withdraws.
+fn withdraw_breed(
+    deps: DepsMut,
+    env: &Env,
+    sender_address: &CanonicalAddr,
+    breed_id: u64,
+    withdrawer: Withdrawer,
+) -> Result<BreedMessages, ContractError> {
+    let mut breed = match read_breed(deps.storage, breed_id)? {
+        Some(breed) => breed,
+        None => return Err(ContractError::not_found("Breed")),
+    };
+
+    if breed.receipt_contract_addr.is_some() && withdrawer != Withdrawer::ReceiptHolder {
+        return Err(ContractError::ReceiptRequired { breed_id });
+    }
+    let keeper_claim = withdrawer == Withdrawer::Keeper;
+    if keeper_claim {
+        if breed.auto_claim_tip.is_none() {
+            return Err(ContractError::AutoClaimDisabled { breed_id });
+        }
+    } else if withdrawer == Withdrawer::Owner && *sender_address != breed.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    let holder = if keeper_claim { breed.owner.clone() } else { sender_address.clone() };
+
+    if env.block.time.seconds() < breed.end_time {
+        return Err(ContractError::BreedNotFinished { breed_id });
+    }
+
+    if breed.is_withdrawn {
+        return Err(ContractError::AlreadyWithdrawn { breed_id });
+    }
+
+    // The breed went through, so its fee is no longer refundable
+    let tip = breed.auto_claim_tip.unwrap_or_default();
+    let mut ledger = read_fee_ledger(deps.storage, &breed.fee_denom)?;
+    ledger.escrowed = ledger.escrowed.checked_sub(breed.fee_amount)?;
+    ledger.earned += breed.fee_amount;
+    ledger.tips = ledger.tips.checked_sub(tip)?;
+    save_fee_ledger(deps.storage, &ledger)?;
+
+    // The child is named by the naming configured when it is minted
+    let config = read_config(deps.storage)?.config;
+    let child_token_id = child_token_id(
+        &config.child_token_id_scheme,
+        breed.id,
+        Some((breed.nft_token_id1.as_str(), breed.nft_token_id2.as_str())),
+    );
+    let child_token_uri = child_token_uri(&config.child_base_uri, &config.child_uri_template, &child_token_id);
+    let breeder = breed.breeder.clone().unwrap_or_else(|| breed.owner.clone());
+    let child_info = ChildInfo {
+        token_uri: child_token_uri.clone(),
+        metadata: None,
+    };
+    record_child(deps.storage, &config, &child_token_id, &child_info, Some(&breeder))?;
+
+    breed.is_withdrawn = true;
+    breed.child_token_id = Some(child_token_id.clone());
+    save_breed(deps.storage, breed_id, &breed)?;
+    remove_owner_breed(deps.storage, &breed.owner, breed_id);
+    remove_auto_claim_breed(deps.storage, breed_id);
+    remove_breed_offer(deps.storage, breed_id);
+
+    let mut breed_count = read_breed_count(deps.storage)?;
+    breed_count.minted += 1;
+    save_breed_count(deps.storage, &breed_count)?;
+    record_stats(deps.storage, env, &breed_count, &holder, 0, 1)?;
+    update_leaderboard(deps.storage, &LeaderboardKind::Breeders, &holder, None, |completed| Ok(completed + Uint128::from(1u128)))?;
+
+    for (collection, token_id) in [
+        (&breed.nft_contract_addr1, &breed.nft_token_id1),
+        (&breed.nft_contract_addr2, &breed.nft_token_id2),
+    ]
+    .iter()
+    {
+        let count = read_breeded_count(deps.storage, collection, token_id)?;
+        save_breeded_count(deps.storage, collection, token_id, count + 1)?;
+        update_leaderboard(deps.storage, &LeaderboardKind::Parents, collection, Some(token_id.as_str()), |_| Ok(Uint128::from((count + 1) as u128)))?;
+    }
+
+    // Parents and child go to the breed owner, or to the receipt holder whose receipt stays here
+    let owner = deps.api.addr_humanize(&holder)?;
+    let mut messages = return_parents_msgs(deps.as_ref(), &breed, owner.as_str())?;
+    messages.push(mint_child_msg(
+        deps.as_ref(),
+        &breed.child_contract_addr,
+        child_token_id.clone(),
+        owner.as_str(),
+        None,
+    )?);
+    if !tip.is_zero() {
+        messages.push(fee_transfer_msg(deps.as_ref(), &breed.fee_denom, tip, deps.api.addr_humanize(sender_address)?.to_string())?);
+    }
+
+    let action = if keeper_claim { events::ACTION_CLAIM_FOR } else { events::ACTION_WITHDRAW };
+    let mut attributes = breed_attributes(deps.as_ref(), action, &breed)?;
+    attributes.push(attr(events::CHILD_CONTRACT, deps.api.addr_humanize(&breed.child_contract_addr)?));
+    attributes.push(attr(events::CHILD_TOKEN_ID, &child_token_id));
+    if let Some(child_token_uri) = child_token_uri {
+        attributes.push(attr(events::CHILD_TOKEN_URI, child_token_uri));
+    }
+    if breed.receipt_contract_addr.is_some() {
+        attributes.push(attr(events::RECIPIENT, &owner));
+    }
+    if keeper_claim {
+        attributes.push(attr(events::SENDER, deps.api.addr_humanize(sender_address)?));
+        attributes.push(attr(events::TIP, tip));
+    }
+
+    let mut submessages = hook_submsgs(deps.as_ref(), BreedingHookMsg::BreedWithdrawn { breed_id, owner: owner.to_string() })?;
+    submessages.extend(hook_submsgs(
+        deps.as_ref(),
+        BreedingHookMsg::ChildMinted {
+            breed_id: Some(breed_id),
+            child_contract_addr: deps.api.addr_humanize(&breed.child_contract_addr)?.to_string(),
+            token_id: child_token_id,
+            owner: owner.to_string(),
+        },
+    )?);
+
+    Ok((messages, submessages, attributes))
+}
+
+fn withdraw_fund(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: Option<FeeDenomMsg>,
+    amount: Option<Uint128>,
+    recipient: Option<String>,
+) -> Result<Response, ContractError> {
+    // Ensure only the contract owner can withdraw funds
+    let config = read_config(deps.storage)?;
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // Only earned fees can leave the contract, escrowed fees and refunds stay untouched
+    let ledgers = match denom {
+        Some(denom) => {
+            let denom = fee_denom_from_msg(deps.as_ref(), denom)?;
+            vec![read_fee_ledger(deps.storage, &denom)?]
+        }
+        None => {
+            if amount.is_some() {
+                return Err(ContractError::DenomRequired {});
+            }
+            read_fee_ledgers(deps.storage)?
+        }
+    };
+
+    let mut withdrawals: Vec<(FeeDenom, Uint128)> = vec![];
+    for mut ledger in ledgers {
+        let available = ledger.available();
+        let withdraw_amount = amount.unwrap_or(available);
+        if withdraw_amount > available {
+            return Err(ContractError::InsufficientFunds {
+                requested: withdraw_amount,
+                available,
+            });
+        }
+        if withdraw_amount.is_zero() {
+            continue;
+        }
+        ledger.withdrawn += withdraw_amount;
+        save_fee_ledger(deps.storage, &ledger)?;
+        withdrawals.push((ledger.denom, withdraw_amount));
+    }
+
+    // Ensure the contract has earned funds to withdraw
+    if withdrawals.is_empty() {
+        return Err(ContractError::NothingToWithdraw {});
+    }
+
+    let mut withdrawn = vec![];
+    for (denom, amount) in withdrawals.iter() {
+        withdrawn.push(format!("{}{}", amount, fee_denom_label(deps.as_ref(), denom)?));
+    }
+    let mut attributes = vec![
+        attr(events::ACTION, events::ACTION_WITHDRAW_FUND),
+        attr(events::SENDER, &info.sender),
+        attr(events::AMOUNT, withdrawn.join(",")),
+    ];
+    if let Some(recipient) = &recipient {
+        attributes.push(attr(events::RECIPIENT, recipient));
+    }
+
+    // An explicit recipient receives everything, otherwise the payout table applies
+    let payouts = match recipient {
+        Some(recipient) => vec![(deps.api.addr_canonicalize(&recipient)?, withdrawals)],
+        None => split_payouts(&read_payout_table(deps.storage)?, &withdrawals),
+    };
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for (recipient, amounts) in payouts {
+        let recipient = deps.api.addr_humanize(&recipient)?;
+        // Native coins of a recipient are grouped into a single bank message
+        let mut coins: Vec<Coin> = vec![];
+        for (denom, amount) in amounts {
+            match denom {
+                FeeDenom::Native(denom) => coins.push(Coin { denom, amount }),
+                cw20 => messages.push(fee_transfer_msg(deps.as_ref(), &cw20, amount, recipient.to_string())?),
+            }
+        }
+        if !coins.is_empty() {
+            messages.push(CosmosMsg::Bank(BankMsg::Send {
+                to_address: recipient.to_string(),
+                amount: coins,
+            }));
+        }
+    }
+
+    Ok(Response {
+        messages,
+        attributes,
+        ..Response::default()
+    })
+}
+
+// Builds the message paying `amount` of a fee denom to `recipient`
+fn fee_transfer_msg(deps: Deps, denom: &FeeDenom, amount: Uint128, recipient: String) -> StdResult<CosmosMsg> {
+    match denom {
+        FeeDenom::Native(denom) => Ok(CosmosMsg::Bank(BankMsg::Send {
+            to_address: recipient,
+            amount: vec![Coin {
+                denom: denom.clone(),
+                amount,
+            }],
+        })),
+        FeeDenom::Cw20(token) => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: deps.api.addr_humanize(token)?.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: recipient.to_string(),
+                amount,
+            })?,
+            send: vec![],
+        })),
+    }
+}
+
+fn fee_denom_from_msg(deps: Deps, denom: FeeDenomMsg) -> StdResult<FeeDenom> {
+    match denom {
+        FeeDenomMsg::Native(denom) => Ok(FeeDenom::Native(denom)),
+        FeeDenomMsg::Cw20(addr) => Ok(FeeDenom::Cw20(deps.api.addr_canonicalize(&addr)?)),
+    }
+}
+
+fn fee_denom_to_msg(deps: Deps, denom: FeeDenom) -> StdResult<FeeDenomMsg> {
+    match denom {
+        FeeDenom::Native(denom) => Ok(FeeDenomMsg::Native(denom)),
+        FeeDenom::Cw20(addr) => Ok(FeeDenomMsg::Cw20(deps.api.addr_humanize(&addr)?.to_string())),
+    }
+}
+
+// Denom as reported in attributes, CW20 tokens are prefixed to tell them apart from native denoms
+fn fee_denom_label(deps: Deps, denom: &FeeDenom) -> StdResult<String> {
+    match denom {
+        FeeDenom::Native(denom) => Ok(denom.clone()),
+        FeeDenom::Cw20(addr) => Ok(format!("cw20:{}", deps.api.addr_humanize(addr)?)),
+    }
+}
+
+// Attributes shared by every action on a breed
+fn breed_attributes(deps: Deps, action: &str, breed: &Breed) -> StdResult<Vec<Attribute>> {
+    Ok(vec![
+        attr(events::ACTION, action),
+        attr(events::BREED_ID, breed.id),
+        attr(events::OWNER, deps.api.addr_humanize(&breed.owner)?),
+        attr(events::PARENT1_CONTRACT, deps.api.addr_humanize(&breed.nft_contract_addr1)?),
+        attr(events::PARENT1_TOKEN_ID, &breed.nft_token_id1),
+        attr(events::PARENT2_CONTRACT, deps.api.addr_humanize(&breed.nft_contract_addr2)?),
+        attr(events::PARENT2_TOKEN_ID, &breed.nft_token_id2),
+    ])
+}
+
+// Splits every amount across the payout shares, the rounding dust of each denom goes to the dust recipient
+fn split_payouts(payout_table: &PayoutTable, amounts: &[(FeeDenom, Uint128)]) -> Vec<(CanonicalAddr, Vec<(FeeDenom, Uint128)>)> {
+    let mut payouts: Vec<(CanonicalAddr, Vec<(FeeDenom, Uint128)>)> = vec![];
+    let mut credit = |recipient: &CanonicalAddr, amount: Uint128, denom: &FeeDenom| {
+        if amount.is_zero() {
+            return;
+        }
+        let amounts = match payouts.iter().position(|(addr, _)| addr == recipient) {
+            Some(index) => &mut payouts[index].1,
+            None => {
+                payouts.push((recipient.clone(), vec![]));
+                &mut payouts.last_mut().unwrap().1
+            }
+        };
+        // A recipient listed in the shares can also be the dust recipient
+        match amounts.iter_mut().find(|(d, _)| d == denom) {
+            Some((_, existing)) => *existing += amount,
+            None => amounts.push((denom.clone(), amount)),
+        }
+    };
+
+    for (denom, total) in amounts {
+        let mut distributed = Uint128::zero();
+        for share in payout_table.shares.iter() {
+            let amount = total.multiply_ratio(share.share_bps, BPS_TOTAL);
+            distributed += amount;
+            credit(&share.recipient, amount, denom);
+        }
+        let dust = total.checked_sub(distributed).unwrap_or_default();
+        credit(&payout_table.dust_recipient, dust, denom);
+    }
+
+    payouts
+}
+
+fn update_payout_table(
+    deps: DepsMut,
+    info: MessageInfo,
+    shares: Vec<PayoutShareMsg>,
+    dust_recipient: String,
+) -> Result<Response, ContractError> {
+    let config = read_config(deps.storage)?;
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if shares.is_empty() {
+        return Err(ContractError::InvalidPayoutTable {
+            reason: "at least one recipient is required".to_string(),
+        });
+    }
+
+    let mut total: u32 = 0;
+    let mut payout_shares: Vec<PayoutShare> = vec![];
+    for share in shares {
+        if share.share_bps == 0 {
+            return Err(ContractError::InvalidPayoutTable {
+                reason: "shares must be greater than zero".to_string(),
+            });
+        }
+        let recipient = deps.api.addr_canonicalize(&share.recipient)?;
+        if payout_shares.iter().any(|s| s.recipient == recipient) {
+            return Err(ContractError::InvalidPayoutTable {
+                reason: "duplicate recipient".to_string(),
+            });
+        }
+        total += share.share_bps as u32;
+        payout_shares.push(PayoutShare {
+            recipient,
+            share_bps: share.share_bps,
+        });
+    }
+
+    if total != BPS_TOTAL as u32 {
+        return Err(ContractError::InvalidPayoutTable {
+            reason: "shares must sum to 10000 basis points".to_string(),
+        });
+    }
+
+    let payout_table = PayoutTable {
+        shares: payout_shares,
+        dust_recipient: deps.api.addr_canonicalize(&dust_recipient)?,
+    };
+    save_payout_table(deps.storage, &payout_table)?;
+
+    let mut attributes = vec![attr(events::ACTION, events::ACTION_UPDATE_PAYOUT_TABLE)];
+    for share in payout_table.shares.iter() {
+        attributes.push(attr(events::SHARE, format!("{}:{}", deps.api.addr_humanize(&share.recipient)?, share.share_bps)));
+    }
+    attributes.push(attr(events::DUST_RECIPIENT, dust_recipient));
+
+    Ok(Response {
+        attributes,
+        ..Response::default()
+    })
+}
+
+fn query_config(deps: Deps) -> Result<ConfigResponse, ContractError> {
+    let config_state = read_config(deps.storage)?;
+    let config = config_state.config;
+    Ok(ConfigResponse {
+        breed_count_limit: config.breed_count_limit,
+        breed_duration: config.breed_duration,
+        breed_price_amount: config.breed_price_amount,
+        breed_price_denom: config.breed_price_denom,
+        breed_price_cw20: match config.breed_price_cw20 {
+            Some(addr) => Some(deps.api.addr_humanize(&addr)?.to_string()),
+            None => None,
+        },
+        pricing: config.pricing,
+        child_contract_addr: match config.child_contract_addr {
+            Some(addr) => Some(deps.api.addr_humanize(&addr)?.to_string()),
+            None => None,
+        },
+        paused: config.paused,
+        max_batch_size: config.max_batch_size,
+        receipt_contract_addr: match config.receipt_contract_addr {
+            Some(addr) => Some(deps.api.addr_humanize(&addr)?.to_string()),
+            None => None,
+        },
+        child_token_id_scheme: config.child_token_id_scheme,
+        child_base_uri: config.child_base_uri,
+        child_uri_template: config.child_uri_template,
+        royalty: match config.royalty {
+            Some(royalty) => Some(RoyaltyMsg {
+                payment_address: deps.api.addr_humanize(&royalty.payment_address)?.to_string(),
+                percentage: royalty.percentage,
+                parent_share_bps: royalty.parent_share_bps,
+            }),
+            None => None,
+        },
+        owner: deps.api.addr_humanize(&config_state.owner)?.to_string(),
+    })
+}
+
+fn query_payout_table(deps: Deps) -> Result<PayoutTableResponse, ContractError> {
+    let payout_table = read_payout_table(deps.storage)?;
+    let mut shares = vec![];
+    for share in payout_table.shares {
+        shares.push(PayoutShareMsg {
+            recipient: deps.api.addr_humanize(&share.recipient)?.to_string(),
+            share_bps: share.share_bps,
+        });
+    }
+    Ok(PayoutTableResponse {
+        shares,
+        dust_recipient: deps.api.addr_humanize(&payout_table.dust_recipient)?.to_string(),
+    })
+}
+
+fn query_treasury(deps: Deps) -> Result<TreasuryResponse, ContractError> {
+    let mut entries = vec![];
+    for ledger in read_fee_ledgers(deps.storage)? {
+        let available = ledger.available();
+        entries.push(TreasuryEntry {
+            denom: fee_denom_to_msg(deps, ledger.denom)?,
+            earned: ledger.earned,
+            withdrawn: ledger.withdrawn,
+            escrowed: ledger.escrowed,
+            tips: ledger.tips,
+            available,
+        });
+    }
+    Ok(TreasuryResponse { entries })
+}
+
+fn query_current_price(deps: Deps, collection1: String, collection2: String) -> Result<CurrentPriceResponse, ContractError> {
+    let config = read_config(deps.storage)?;
+    let breed_count = read_breed_count(deps.storage)?;
+    let collection1 = deps.api.addr_canonicalize(&collection1)?;
+    let collection2 = deps.api.addr_canonicalize(&collection2)?;
+    let pairing = match read_pairing(deps.storage, &collection1, &collection2)? {
+        Some(pairing) => pairing,
+        None => return Err(ContractError::not_found("Pairing")),
+    };
+    let denom = match &config.config.breed_price_cw20 {
+        Some(token) => FeeDenom::Cw20(token.clone()),
+        None => FeeDenom::Native(config.config.breed_price_denom.clone()),
+    };
+    Ok(CurrentPriceResponse {
+        amount: current_price(&config.config, pairing.breed_price_amount, breed_count.minted)?,
+        denom: fee_denom_to_msg(deps, denom)?,
+        minted: breed_count.minted,
+    })
+}
+
+fn query_allowlist_status(deps: Deps, address: String, proof: Vec<String>) -> Result<AllowlistStatusResponse, ContractError> {
+    let round = match read_allowlist(deps.storage)? {
+        Some(round) => round,
+        None => {
+            return Ok(AllowlistStatusResponse {
+                round_id: None,
+                eligible: false,
+                discount_bps: 0,
+                quota: 0,
+                used: 0,
+                remaining: 0,
+            })
+        }
+    };
+
+    let addr = deps.api.addr_canonicalize(&address)?;
+    let used = read_allowlist_usage(deps.storage, round.id, &addr)?;
+    let eligible = verify_allowlist_proof(&round.merkle_root, &address, &proof)?;
+    let remaining = if eligible { round.quota.saturating_sub(used) } else { 0 };
+
+    Ok(AllowlistStatusResponse {
+        round_id: Some(round.id),
+        eligible,
+        discount_bps: round.discount_bps,
+        quota: round.quota,
+        used,
+        remaining,
+    })
+}
+
+fn query_parent_collections(deps: Deps) -> Result<ParentCollectionsResponse, ContractError> {
+    let mut collections = vec![];
+    for collection in read_parent_collections(deps.storage)? {
+        collections.push(deps.api.addr_humanize(&collection.addr)?.to_string());
+    }
+    Ok(ParentCollectionsResponse { collections })
+}
+
+fn query_leaderboard(deps: Deps, kind: LeaderboardKindMsg, start_after: Option<(Uint128, String)>, limit: Option<u32>) -> Result<LeaderboardResponse, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let kind = match kind {
+        LeaderboardKindMsg::Breeders {} => LeaderboardKind::Breeders,
+        LeaderboardKindMsg::Parents {} => LeaderboardKind::Parents,
+        LeaderboardKindMsg::Spenders { denom } => LeaderboardKind::Spenders(fee_denom_from_msg(deps, denom)?),
+    };
+
+    // The page resumes below the rank of the last entry, whose score may have changed since
+    let start_after = match start_after {
+        Some((score, key)) => {
+            let entry_key = match kind {
+                LeaderboardKind::Parents => {
+                    let (collection, token_id) = key.split_once('/').ok_or(ContractError::InvalidStartAfter {})?;
+                    leaderboard_entry_key(&deps.api.addr_canonicalize(collection)?, Some(token_id))
+                }
+                _ => leaderboard_entry_key(&deps.api.addr_canonicalize(&key)?, None),
+            };
+            Some(leaderboard_rank_key(score, &entry_key))
+        }
+        None => None,
+    };
+
+    let (above, board) = read_leaderboard(deps.storage, &kind, start_after.as_deref(), limit)?;
+    let mut entries = vec![];
+    for (index, entry) in board.into_iter().enumerate() {
+        entries.push(LeaderboardEntryResponse {
+            rank: (above + index) as u32 + 1,
+            address: deps.api.addr_humanize(&entry.address)?.to_string(),
+            token_id: entry.token_id,
+            score: entry.score,
+        });
+    }
+    Ok(LeaderboardResponse { entries })
+}
+
+fn query_hooks(deps: Deps) -> Result<HooksResponse, ContractError> {
+    let mut hooks = vec![];
+    for hook in read_hooks(deps.storage)? {
+        hooks.push(HookResponse {
+            addr: deps.api.addr_humanize(&hook.addr)?.to_string(),
+            events: hook.events,
+        });
+    }
+    Ok(HooksResponse { hooks })
+}
+
+fn query_preview_child_uri(
+    deps: Deps,
+    id: u64,
+    child_base_uri: Option<String>,
+    child_uri_template: Option<String>,
+) -> Result<PreviewChildUriResponse, ContractError> {
+    let config = read_config(deps.storage)?.config;
+    let base_uri = match child_base_uri {
+        Some(base_uri) => Some(base_uri).filter(|uri| !uri.is_empty()),
+        None => config.child_base_uri,
+    };
+    let template = match child_uri_template {
+        Some(template) => Some(template).filter(|template| !template.is_empty()),
+        None => config.child_uri_template,
+    };
+    validate_child_naming(&config.child_token_id_scheme, &base_uri, &template)?;
+
+    // Hashed ids depend on the parents, which are only known for an existing breed
+    let breed = read_breed(deps.storage, id)?;
+    let parents = breed.as_ref().map(|breed| (breed.nft_token_id1.as_str(), breed.nft_token_id2.as_str()));
+    let token_id = child_token_id(&config.child_token_id_scheme, id, parents);
+    let token_uri = child_token_uri(&base_uri, &template, &token_id);
+    Ok(PreviewChildUriResponse { token_id, token_uri })
+}
+
+fn query_child_info(deps: Deps, token_id: String) -> Result<ChildInfoResponse, ContractError> {
+    let info = match read_child_info(deps.storage, &token_id)? {
+        Some(info) => info,
+        None => return Err(ContractError::not_found("Child")),
+    };
+    Ok(ChildInfoResponse {
+        token_uri: info.token_uri,
+        metadata: info.metadata,
+    })
+}
+
+// Marketplaces pay the payment address directly, the parent share is only split through PayRoyalty
+// CW-2981 answers a child without a royalty with a zero amount and an empty address
+fn query_royalty_info(deps: Deps, token_id: String, sale_price: Uint128) -> Result<RoyaltiesInfoResponse, ContractError> {
+    let royalty = match read_child_royalty(deps.storage, &token_id)? {
+        Some(royalty) => royalty,
+        None => {
+            return Ok(RoyaltiesInfoResponse {
+                address: String::new(),
+                royalty_amount: Uint128::zero(),
+            })
+        }
+    };
+    Ok(RoyaltiesInfoResponse {
+        address: deps.api.addr_humanize(&royalty.payment_address)?.to_string(),
+        royalty_amount: sale_price.multiply_ratio(royalty.percentage, MAX_ROYALTY_PERCENTAGE),
+    })
+}
+
+// Whether newly minted children carry a royalty, children minted earlier keep theirs
+fn query_check_royalties(deps: Deps) -> Result<CheckRoyaltiesResponse, ContractError> {
+    let config = read_config(deps.storage)?.config;
+    Ok(CheckRoyaltiesResponse {
+        royalty_payments: config.royalty.is_some(),
+    })
+}
+
+fn query_pairings(deps: Deps) -> Result<PairingsResponse, ContractError> {
+    let mut pairings = vec![];
+    for pairing in read_pairings(deps.storage)? {
+        pairings.push(PairingResponse {
+            collection1: deps.api.addr_humanize(&pairing.collection1)?.to_string(),
+            collection2: deps.api.addr_humanize(&pairing.collection2)?.to_string(),
+            breed_price_amount: pairing.breed_price_amount,
+            breed_duration: pairing.breed_duration,
+            child_contract_addr: match pairing.child_contract_addr {
+                Some(addr) => Some(deps.api.addr_humanize(&addr)?.to_string()),
+                None => None,
+            },
+        });
+    }
+    Ok(PairingsResponse { pairings })
+}
+
+// Walks the unsettled auto-claim breeds after `start_after` for keeper bots, skipping those still incubating
+fn query_claimable_breeds(deps: Deps, env: Env, start_after: Option<u64>, limit: Option<u32>) -> Result<ClaimableBreedsResponse, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let start = start_after.map(|breed_id| (breed_id + 1).to_be_bytes());
+
+    let mut breeds = vec![];
+    let auto_claim_breeds = bucket_read::<u64>(deps.storage, AUTO_CLAIM_BREEDS_KEY);
+    for item in auto_claim_breeds.range(start.as_ref().map(|start| start.as_ref()), None, Order::Ascending) {
+        if breeds.len() >= limit {
+            break;
+        }
+        let breed = match read_breed(deps.storage, item?.1)? {
+            Some(breed) => breed,
+            None => continue,
+        };
+        // Receipt breeds from before auto-claim was closed to them cannot be claimed either
+        if breed.is_withdrawn || breed.receipt_contract_addr.is_some() || env.block.time.seconds() < breed.end_time {
+            continue;
+        }
+        if let Some(tip) = breed.auto_claim_tip {
+            breeds.push(ClaimableBreed {
+                breed_id: breed.id,
+                owner: deps.api.addr_humanize(&breed.owner)?.to_string(),
+                end_time: breed.end_time,
+                tip,
+                denom: fee_denom_to_msg(deps, breed.fee_denom)?,
+            });
+        }
+    }
+    Ok(ClaimableBreedsResponse { breeds })
+}
+
+fn query_breed_info(deps: Deps, breed_id: u64) -> Result<BreedInfoResponse, ContractError> {
+    let breed = match read_breed(deps.storage, breed_id)? {
+        Some(breed) => breed,
+        None => return Err(ContractError::not_found("Breed")),
+    };
+    breed_info_response(deps, breed)
+}
+
+fn breed_info_response(deps: Deps, breed: Breed) -> Result<BreedInfoResponse, ContractError> {
+    Ok(BreedInfoResponse {
+        child_token_id: if breed.is_withdrawn {
+            Some(breed.child_token_id.clone().unwrap_or_else(|| breed.id.to_string()))
+        } else {
+            None
+        },
+        end_time: breed.end_time,
+        nft_owner: deps.api.addr_humanize(&breed.owner)?.to_string(),
+        nft_token_id1: breed.nft_token_id1,
+        nft_token_id2: breed.nft_token_id2,
+        start_time: breed.start_time,
+        withdrawn: breed.is_withdrawn,
+    })
+}
+
+fn query_breed_requests_count(deps: Deps) -> Result<BreedRequestsCountResponse, ContractError> {
+    let breed_count = read_breed_count(deps.storage)?;
+    Ok(breed_count.count as u32)
+}
+
+fn query_breed_finished_count(deps: Deps) -> Result<BreedFinishedCountResponse, ContractError> {
+    // Every finished breed mints exactly one child, direct mints are not breeds
+    let breed_count = read_breed_count(deps.storage)?;
+    Ok((breed_count.minted - breed_count.direct_minted) as u32)
+}
+
+// Per-address counters only cover activity since snapshots were introduced
+fn query_breed_stats_at_height(deps: Deps, address: String, height: u64) -> Result<BreedStatsResponse, ContractError> {
+    let address = deps.api.addr_canonicalize(&address)?;
+    let stats = read_stats(deps.storage, Some(&address), Some(height))?;
+    Ok(BreedStatsResponse {
+        height,
+        breeds: stats.breeds,
+        children: stats.children,
+    })
+}
+
+fn query_total_stats_at_height(deps: Deps, height: u64) -> Result<BreedStatsResponse, ContractError> {
+    let stats = read_stats(deps.storage, None, Some(height))?;
+    Ok(BreedStatsResponse {
+        height,
+        breeds: stats.breeds,
+        children: stats.children,
+    })
+}
+
+fn query_breeded_count(deps: Deps, parent_nft_contract_addr: String, parent_nft_token_id: String) -> Result<BreededCountResponse, ContractError> {
+    let collection = deps.api.addr_canonicalize(&parent_nft_contract_addr)?;
+    Ok(read_breeded_count(deps.storage, &collection, &parent_nft_token_id)?)
+}
+
+fn query_breedings(
+    deps: Deps,
+    count: u32,
+    from: u32,
+    sort: String,
+) -> Result<QueryBreedingsResponse, ContractError> {
+    let breedings = sort_breedings(read_breeds(deps.storage)?, &sort);
+
+    let mut breed_info = vec![];
+    for breed in breedings.into_iter().skip(from as usize).take(count as usize) {
+        breed_info.push(breed_info_response(deps, breed)?);
+    }
+    Ok(QueryBreedingsResponse { breed_info })
+}
+
+fn sort_breedings(breedings: Vec<Breed>, sort: &str) -> Vec<Breed> {
+    let mut sorted_breedings = breedings;
+
+    // Breeds are sorted by start time, ascending unless descending is requested
+    match sort {
+        "descending" => {
+            sorted_breedings.sort_by_key(|breed| Reverse(breed.start_time));
+        }
+        _ => {
+            sorted_breedings.sort_by_key(|breed| breed.start_time);
+        }
+    }
+
+    sorted_breedings
+}
+
+fn query_breedings_length(deps: Deps) -> Result<QueryBreedingsLengthResponse, ContractError> {
+    Ok(read_breeds(deps.storage)?.len() as u32)
+}
+
+fn query_user_breedings(
+    deps: Deps,
+    count: u32,
+    from: u32,
+    sort: String,
+    user: String,
+) -> Result<QueryUserBreedingsResponse, ContractError> {
+    // The owner index only holds unsettled breeds, withdrawn ones are listed too
+    let owner = deps.api.addr_canonicalize(&user)?;
+    let user_breedings = read_breeds(deps.storage)?
+        .into_iter()
+        .filter(|breed| breed.owner == owner)
+        .collect();
+
+    let mut breed_info = vec![];
+    for breed in sort_breedings(user_breedings, &sort).into_iter().skip(from as usize).take(count as usize) {
+        breed_info.push(breed_info_response(deps, breed)?);
+    }
+    Ok(breed_info)
+}
+
+fn query_user_breedings_length(deps: Deps, user: String) -> Result<QueryUserBreedingsLengthResponse, ContractError> {
+    let owner = deps.api.addr_canonicalize(&user)?;
+    let length = read_breeds(deps.storage)?
+        .iter()
+        .filter(|breed| breed.owner == owner)
+        .count();
+    Ok(length as u32)
+}
+
+// Data returned by the chain when a contract instantiation succeeds
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgInstantiateContractResponse {
+    #[prost(string, tag = "1")]
+    pub contract_address: String,
+    #[prost(bytes, tag = "2")]
+    pub data: Vec<u8>,
+}
@@ -0,0 +1,160 @@
+// error.rs
+
+use cosmwasm_std::{OverflowError, StdError, Uint128};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Breeding is paused by governance")]
+    Paused {},
+
+    #[error("{kind} not found")]
+    NotFound { kind: String },
+
+    #[error("Breed {breed_id} has not finished yet")]
+    BreedNotFinished { breed_id: u64 },
+
+    #[error("Breed {breed_id} has already finished")]
+    BreedFinished { breed_id: u64 },
+
+    #[error("Breed {breed_id} has already been withdrawn")]
+    AlreadyWithdrawn { breed_id: u64 },
+
+    #[error("Breed {breed_id} did not opt into auto-claim")]
+    AutoClaimDisabled { breed_id: u64 },
+
+    #[error("Breed {breed_id} is open to auto-claim and cannot be transferred")]
+    BreedBeingClaimed { breed_id: u64 },
+
+    #[error("Breed {breed_id} is held through its receipt, transfer the receipt instead")]
+    ReceiptBreed { breed_id: u64 },
+
+    #[error("Breed {breed_id} is held through its receipt, send the receipt to settle it")]
+    ReceiptRequired { breed_id: u64 },
+
+    #[error("Breeds held through a receipt cannot opt into auto-claim")]
+    ReceiptAutoClaim {},
+
+    #[error("Only the receipt of a breed can be sent to this contract, along with a ReceiptMsg")]
+    InvalidReceipt {},
+
+    #[error("Maximum breed count of {limit} reached")]
+    LimitReached { limit: u32 },
+
+    #[error("Breed price is {expected}, received {received}")]
+    WrongFunds { expected: Uint128, received: Uint128 },
+
+    #[error("Unexpected funds sent in {denom}")]
+    UnexpectedFunds { denom: String },
+
+    #[error("Breed price must be paid with the configured CW20 token")]
+    Cw20PaymentRequired {},
+
+    #[error("Breed price is not payable with this CW20 token")]
+    InvalidCw20Token {},
+
+    #[error("A token cannot breed with itself")]
+    SelfBreeding {},
+
+    #[error("Batch must contain at least one pair")]
+    EmptyBatch {},
+
+    #[error("Batch cannot contain more than {max} pairs")]
+    BatchTooLarge { max: u32 },
+
+    #[error("Parent token {token_id} appears more than once in the batch")]
+    DuplicateParent { token_id: String },
+
+    #[error("Parent token {token_id} is not owned by the breeder")]
+    NotParentOwner { token_id: String },
+
+    #[error("These collections are not paired for breeding")]
+    NotPaired {},
+
+    #[error("Only registered parent collections can be paired")]
+    UnregisteredCollection {},
+
+    #[error("Remove the pairings of this collection first")]
+    CollectionInUse {},
+
+    #[error("No child collection has been instantiated")]
+    NoChildCollection {},
+
+    #[error("Invalid pricing: {reason}")]
+    InvalidPricing { reason: String },
+
+    #[error("Invalid metadata: {reason}")]
+    InvalidMetadata { reason: String },
+
+    #[error("Invalid royalty: {reason}")]
+    InvalidRoyalty { reason: String },
+
+    #[error("Royalty payments must send funds")]
+    NoRoyaltyFunds {},
+
+    #[error("Invalid child naming: {reason}")]
+    InvalidChildNaming { reason: String },
+
+    #[error("Breed price overflow")]
+    PriceOverflow {},
+
+    #[error("No allowlist round is active")]
+    NoAllowlist {},
+
+    #[error("Invalid allowlist proof")]
+    InvalidProof {},
+
+    #[error("Allowlist quota used up")]
+    QuotaExceeded {},
+
+    #[error("Expected a hex encoded sha256 hash")]
+    InvalidHash {},
+
+    #[error("Discount cannot exceed 10000 basis points")]
+    InvalidDiscount {},
+
+    #[error("Invalid payout table: {reason}")]
+    InvalidPayoutTable { reason: String },
+
+    #[error("A denom is required when withdrawing a specific amount")]
+    DenomRequired {},
+
+    #[error("Cannot withdraw {requested}, only {available} is available")]
+    InsufficientFunds { requested: Uint128, available: Uint128 },
+
+    #[error("No earned funds to withdraw")]
+    NothingToWithdraw {},
+
+    #[error("Invalid hook: {reason}")]
+    InvalidHook { reason: String },
+
+    #[error("Unknown reply id {id}")]
+    UnknownReplyId { id: u64 },
+
+    #[error("Invalid instantiate reply: {reason}")]
+    InvalidReply { reason: String },
+
+    #[error("Invalid hook reply: {reason}")]
+    InvalidHookReply { reason: String },
+
+    #[error("Leaderboard start_after must be the score and key of an entry")]
+    InvalidStartAfter {},
+
+    #[error("Protobuf queries cannot be nested")]
+    NestedProtobufQuery {},
+}
+
+impl ContractError {
+    pub fn not_found(kind: impl Into<String>) -> Self {
+        ContractError::NotFound { kind: kind.into() }
+    }
+}
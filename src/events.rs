@@ -0,0 +1,108 @@
+// events.rs
+//
+// Attribute keys and action names emitted by every state changing handler. Indexers depend on
+// them, so they are a stable interface: new keys may be added, existing ones are never renamed,
+// removed or given a different meaning.
+//
+// Every response starts with a single `action`. Responses covering several breeds report it once,
+// followed by one block of keys per breed that starts with its `breed_id`. Breed actions carry
+// `breed_id` and `owner`, parents are reported as `parent1_contract`/`parent1_token_id` and
+// `parent2_contract`/`parent2_token_id`.
+// Amounts are reported as `amount` with a `denom` that is either a native denom or
+// `cw20:<token address>`.
+
+// Key naming the action, always the first attribute
+pub const ACTION: &str = "action";
+
+// Actions
+pub const ACTION_UPDATE_CONFIG: &str = "update_config";
+pub const ACTION_CHANGE_OWNERSHIP: &str = "change_ownership";
+pub const ACTION_START_BREED: &str = "start_breed";
+pub const ACTION_BREED: &str = "breed";
+pub const ACTION_CANCEL_BREED: &str = "cancel_breed";
+pub const ACTION_WITHDRAW: &str = "withdraw";
+pub const ACTION_MINT: &str = "mint";
+pub const ACTION_WITHDRAW_FUND: &str = "withdraw_fund";
+pub const ACTION_UPDATE_PAYOUT_TABLE: &str = "update_payout_table";
+pub const ACTION_SET_ALLOWLIST: &str = "set_allowlist";
+pub const ACTION_CLOSE_ALLOWLIST: &str = "close_allowlist";
+pub const ACTION_ADD_PARENT_COLLECTION: &str = "add_parent_collection";
+pub const ACTION_REMOVE_PARENT_COLLECTION: &str = "remove_parent_collection";
+pub const ACTION_SET_PAIRING: &str = "set_pairing";
+pub const ACTION_REMOVE_PAIRING: &str = "remove_pairing";
+pub const ACTION_INSTANTIATE_CHILD_COLLECTION: &str = "instantiate_child_collection";
+pub const ACTION_CHILD_COLLECTION_INSTANTIATED: &str = "child_collection_instantiated";
+pub const ACTION_FORCE_PAUSE: &str = "force_pause";
+pub const ACTION_RESCUE_NFTS: &str = "rescue_nfts";
+pub const ACTION_CLAIM_FOR: &str = "claim_for";
+pub const ACTION_INSTANTIATE_RECEIPT_COLLECTION: &str = "instantiate_receipt_collection";
+pub const ACTION_TRANSFER_BREED: &str = "transfer_breed";
+pub const ACTION_OFFER_BREED: &str = "offer_breed";
+pub const ACTION_ACCEPT_BREED: &str = "accept_breed";
+pub const ACTION_ADD_HOOK: &str = "add_hook";
+pub const ACTION_REMOVE_HOOK: &str = "remove_hook";
+pub const ACTION_HOOK_FAILED: &str = "hook_failed";
+pub const ACTION_PAY_ROYALTY: &str = "pay_royalty";
+pub const ACTION_REDEEM_RECEIPT: &str = "redeem_receipt";
+
+// Set to `true` on every response of the sudo entry point, right after `action`
+pub const GOVERNANCE: &str = "governance";
+
+// Breed keys
+pub const BREED_ID: &str = "breed_id";
+pub const OWNER: &str = "owner";
+pub const PARENT1_CONTRACT: &str = "parent1_contract";
+pub const PARENT1_TOKEN_ID: &str = "parent1_token_id";
+pub const PARENT2_CONTRACT: &str = "parent2_contract";
+pub const PARENT2_TOKEN_ID: &str = "parent2_token_id";
+pub const CHILD_CONTRACT: &str = "child_contract";
+pub const CHILD_TOKEN_ID: &str = "child_token_id";
+pub const CHILD_TOKEN_URI: &str = "child_token_uri";
+pub const START_TIME: &str = "start_time";
+pub const END_TIME: &str = "end_time";
+pub const TIP: &str = "tip";
+pub const RECEIPT_CONTRACT: &str = "receipt_contract";
+
+// Fund keys, `amount` may list several comma separated coins when more than one denom moves
+pub const AMOUNT: &str = "amount";
+pub const DENOM: &str = "denom";
+pub const RECIPIENT: &str = "recipient";
+pub const REFUND_RECIPIENT: &str = "refund_recipient";
+pub const SENDER: &str = "sender";
+pub const SHARE: &str = "share";
+pub const DUST_RECIPIENT: &str = "dust_recipient";
+pub const PARENT_OWNER: &str = "parent_owner";
+pub const PARENT_AMOUNT: &str = "parent_amount";
+
+// Config keys, only the fields changed by the message are emitted. The pricing and child token id
+// scheme are emitted as base64 encoded JSON.
+pub const BREED_COUNT_LIMIT: &str = "breed_count_limit";
+pub const BREED_DURATION: &str = "breed_duration";
+pub const BREED_PRICE_AMOUNT: &str = "breed_price_amount";
+pub const BREED_PRICE_DENOM: &str = "breed_price_denom";
+pub const BREED_PRICE_CW20: &str = "breed_price_cw20";
+pub const PRICING: &str = "pricing";
+pub const PREVIOUS_OWNER: &str = "previous_owner";
+pub const NEW_OWNER: &str = "new_owner";
+pub const PAUSED: &str = "paused";
+pub const MAX_BATCH_SIZE: &str = "max_batch_size";
+pub const CHILD_TOKEN_ID_SCHEME: &str = "child_token_id_scheme";
+pub const CHILD_BASE_URI: &str = "child_base_uri";
+pub const CHILD_URI_TEMPLATE: &str = "child_uri_template";
+pub const ROYALTY_PAYMENT_ADDRESS: &str = "royalty_payment_address";
+pub const ROYALTY_PERCENTAGE: &str = "royalty_percentage";
+pub const ROYALTY_PARENT_SHARE_BPS: &str = "royalty_parent_share_bps";
+
+// Hook keys, `hook_events` lists the subscribed events comma separated
+pub const HOOK: &str = "hook";
+pub const HOOK_EVENTS: &str = "hook_events";
+pub const ERROR: &str = "error";
+
+// Registry and allowlist keys
+pub const COLLECTION: &str = "collection";
+pub const COLLECTION1: &str = "collection1";
+pub const COLLECTION2: &str = "collection2";
+pub const ALLOWLIST_ROUND: &str = "allowlist_round";
+pub const MERKLE_ROOT: &str = "merkle_root";
+pub const DISCOUNT_BPS: &str = "discount_bps";
+pub const QUOTA: &str = "quota";
@@ -3,7 +3,40 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{HumanAddr, Uint128};
+use cosmwasm_std::{to_binary, Binary, Coin, CosmosMsg, StdResult, Uint128, WasmMsg};
+use cw20::Cw20ReceiveMsg;
+use cw721::Cw721ReceiveMsg;
+
+use crate::state::{ChildTokenIdScheme, HookEvent, PricingMode};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InitMsg {
+    pub breed_count_limit: u32,
+    pub breed_duration: u64,
+    pub breed_price_amount: Uint128,
+    pub breed_price_denom: String,
+    pub breed_price_cw20: Option<String>,
+    pub pricing: Option<PricingMode>,
+    pub max_batch_size: Option<u32>,
+    pub child_token_id_scheme: Option<ChildTokenIdScheme>,
+    pub child_base_uri: Option<String>,
+    // Template of child token uris such as `{base}/{id}.json`
+    pub child_uri_template: Option<String>,
+    pub royalty: Option<RoyaltyMsg>,
+    // Instantiates the default child collection with this contract as minter
+    pub child_collection: Option<ChildCollectionMsg>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ChildCollectionMsg {
+    pub code_id: u64,
+    pub name: String,
+    pub symbol: String,
+    pub label: String,
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -12,23 +45,165 @@ pub enum HandleMsg {
         breed_count_limit: Option<u32>,
         breed_duration: Option<u64>,
         breed_price_amount: Option<Uint128>,
+        // Switches the breed price to this native denom
         breed_price_denom: Option<String>,
-        owner: Option<HumanAddr>,
+        // Switches the breed price to this CW20 token
+        breed_price_cw20: Option<String>,
+        pricing: Option<PricingMode>,
+        max_batch_size: Option<u32>,
+        child_token_id_scheme: Option<ChildTokenIdScheme>,
+        // An empty base uri or template unsets it
+        child_base_uri: Option<String>,
+        child_uri_template: Option<String>,
+        // A zero percentage unsets the royalty
+        royalty: Option<RoyaltyMsg>,
+        owner: Option<String>,
     },
-    StartBreed {},
     Breed {
-        nft_token_id1: String,
-        nft_token_id2: String,
+        parent1: ParentNft,
+        parent2: ParentNft,
+        proof: Option<Vec<String>>,
+        // Opts into keeper auto-claim, the tip is paid on top of the price
+        auto_claim_tip: Option<Uint128>,
     },
+    // Breeds each (token_id1, token_id2) pair of one pairing, paid with the sum of the breed prices
+    BreedBatch {
+        collection1: String,
+        collection2: String,
+        pairs: Vec<(String, String)>,
+        // Tip per breed of the batch
+        auto_claim_tip: Option<Uint128>,
+    },
+    // Mints a child to the owner outside of breeding, the token id and uri are generated from the
+    // configured child naming
     Mint {
         extension: Option<Metadata>,
+    },
+    // Splits the sent royalty of a child between its payment address and its parents' owner, royalties
+    // paid straight to the payment address are not split
+    PayRoyalty {
         token_id: String,
-        token_uri: Option<String>,
+    },
+    Receive(Cw20ReceiveMsg),
+    // Settles a breed held through a receipt, sent with a ReceiptMsg through a CW721 `SendNft` of the receipt
+    ReceiveNft(Cw721ReceiveMsg),
+    CancelBreed {
+        breed_id: u64,
     },
     Withdraw {
         breed_id: u64,
     },
-    WithdrawFund {},
+    // Withdraws up to `limit` of the caller's finished breeds after `start_after`, oldest first.
+    // Breeds held through a receipt are skipped, they are settled by sending their receipt.
+    WithdrawAll {
+        start_after: Option<u64>,
+        limit: u32,
+    },
+    // Withdraws a finished auto-claim breed to its owner, the caller receives the tip
+    ClaimFor {
+        breed_id: u64,
+    },
+    // Hands a breed that has no receipt to another address, who can then cancel or withdraw it
+    TransferBreed {
+        breed_id: u64,
+        recipient: String,
+    },
+    // Offers a breed to `recipient`, replacing any previous offer, `price` is paid to the owner on accept
+    OfferBreed {
+        breed_id: u64,
+        recipient: String,
+        price: Option<Coin>,
+    },
+    AcceptBreed {
+        breed_id: u64,
+    },
+    WithdrawFund {
+        denom: Option<FeeDenomMsg>,
+        amount: Option<Uint128>,
+        recipient: Option<String>,
+    },
+    UpdatePayoutTable {
+        shares: Vec<PayoutShareMsg>,
+        dust_recipient: String,
+    },
+    // Starts a new allowlist round, quotas used in previous rounds do not carry over
+    SetAllowlist {
+        merkle_root: String,
+        discount_bps: u16,
+        quota: u32,
+    },
+    CloseAllowlist {},
+    AddParentCollection {
+        addr: String,
+    },
+    RemoveParentCollection {
+        addr: String,
+    },
+    // Price, duration and child collection fall back to the config defaults when omitted
+    SetPairing {
+        collection1: String,
+        collection2: String,
+        breed_price_amount: Option<Uint128>,
+        breed_duration: Option<u64>,
+        child_contract_addr: Option<String>,
+    },
+    RemovePairing {
+        collection1: String,
+        collection2: String,
+    },
+    // Instantiates a child collection for a pairing, or the default one when no pairing is given
+    InstantiateChildCollection {
+        collection: ChildCollectionMsg,
+        pairing: Option<(String, String)>,
+    },
+    // Instantiates the collection of breed receipts, later breeds are held by whoever owns their receipt.
+    // A receipt is spent when its breed is settled and stays with this contract from then on.
+    InstantiateReceiptCollection {
+        collection: ChildCollectionMsg,
+    },
+    // Registers a contract to receive a BreedingHookMsg on each of `events`, replacing its previous events
+    AddHook {
+        addr: String,
+        events: Vec<HookEvent>,
+    },
+    RemoveHook {
+        addr: String,
+    },
+}
+
+// Messages chain governance sends through the sudo entry point
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
+pub enum SudoMsg {
+    ForcePause {
+        paused: bool,
+    },
+    ReplaceOwner {
+        owner: String,
+    },
+    UpdateConfig {
+        breed_count_limit: Option<u32>,
+        breed_duration: Option<u64>,
+        breed_price_amount: Option<Uint128>,
+        breed_price_denom: Option<String>,
+        breed_price_cw20: Option<String>,
+        pricing: Option<PricingMode>,
+        max_batch_size: Option<u32>,
+        child_token_id_scheme: Option<ChildTokenIdScheme>,
+        child_base_uri: Option<String>,
+        child_uri_template: Option<String>,
+        royalty: Option<RoyaltyMsg>,
+        child_contract_addr: Option<String>,
+        receipt_contract_addr: Option<String>,
+        paused: Option<bool>,
+    },
+    // Returns the escrowed parents of an unwithdrawn breed to `recipient`, or to its owner. Without a
+    // recipient the parents of a receipt breed wait for its receipt to be sent to this contract.
+    RescueNfts {
+        breed_id: u64,
+        recipient: Option<String>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -39,10 +214,19 @@ pub enum QueryMsg {
         breed_id: u64,
     },
     BreededCount {
+        parent_nft_contract_addr: String,
         parent_nft_token_id: String,
     },
     BreedRequestsCount {},
     BreedFinishedCount {},
+    // Counters as they were at the start of `height`, changes made in that block are not included
+    BreedStatsAtHeight {
+        address: String,
+        height: u64,
+    },
+    TotalStatsAtHeight {
+        height: u64,
+    },
     QueryBreedings {
         count: u32,
         from: u32,
@@ -53,13 +237,390 @@ pub enum QueryMsg {
         count: u32,
         from: u32,
         sort: String,
-        user: HumanAddr,
+        user: String,
     },
     QueryUserBreedingsLength {
-        user: HumanAddr,
+        user: String,
+    },
+    PayoutTable {},
+    Treasury {},
+    CurrentPrice {
+        collection1: String,
+        collection2: String,
+    },
+    AllowlistStatus {
+        address: String,
+        proof: Vec<String>,
+    },
+    ParentCollections {},
+    Pairings {},
+    // Finished breeds waiting for a keeper, in id order
+    ClaimableBreeds {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    Hooks {},
+    // Highest scores first, `start_after` is the score and key of the last entry of the previous page.
+    // The key is the entry address, or `<collection>/<token_id>` on the parents leaderboard.
+    Leaderboard {
+        kind: LeaderboardKindMsg,
+        start_after: Option<(Uint128, String)>,
+        limit: Option<u32>,
+    },
+    // Token id and uri of the child of breed `id`, the overrides are validated and used in place of
+    // the configured base uri and template
+    PreviewChildUri {
+        id: u64,
+        child_base_uri: Option<String>,
+        child_uri_template: Option<String>,
+    },
+    // Token uri and metadata of a child minted by this contract
+    ChildInfo {
+        token_id: String,
+    },
+    // CW-2981 queries, a child without a royalty is answered with a zero amount and an empty address
+    RoyaltyInfo {
+        token_id: String,
+        sale_price: Uint128,
+    },
+    CheckRoyalties {},
+    // Answers the wrapped query with its protobuf encoding, see proto/breeding.proto
+    #[cfg(feature = "protobuf")]
+    Protobuf {
+        query: Box<QueryMsg>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ParentNft {
+    pub collection: String,
+    pub token_id: String,
+}
+
+// Messages accepted through a CW20 `Send` to this contract
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiveMsg {
+    Breed {
+        parent1: ParentNft,
+        parent2: ParentNft,
+        proof: Option<Vec<String>>,
+        // Opts into keeper auto-claim, the tip is paid on top of the price
+        auto_claim_tip: Option<Uint128>,
     },
+    BreedBatch {
+        collection1: String,
+        collection2: String,
+        pairs: Vec<(String, String)>,
+        // Tip per breed of the batch
+        auto_claim_tip: Option<Uint128>,
+    },
+}
+
+// Messages accepted with a breed receipt sent through a CW721 `SendNft` to this contract, the receipt
+// token id is the breed id. cw721-base 0.6 cannot burn, so the contract keeps the spent receipt.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiptMsg {
+    // Withdraws the finished breed to the receipt holder
+    Withdraw {},
+    // Cancels the incubating breed, the parents go to the receipt holder. A rescued breed hands back
+    // its parents on either message.
+    Cancel {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaderboardKindMsg {
+    // Breed holders by completed breeds, a direct mint counts for the owner receiving it
+    Breeders {},
+    // Parent tokens by child count
+    Parents {},
+    // Breeders by fees paid in one denom, refunded fees are not counted
+    Spenders { denom: FeeDenomMsg },
 }
 
-// Define Metadata type if not already defined in the contract.rs file
+// Sent to hook contracts, wrapped as `{"breeding_hook": ...}` in their execute message
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct Metadata(pub Vec<u8>);
+#[serde(rename_all = "snake_case")]
+pub enum BreedingHookMsg {
+    BreedStarted {
+        breed_id: u64,
+        owner: String,
+        parent1: ParentNft,
+        parent2: ParentNft,
+        end_time: u64,
+    },
+    BreedCancelled {
+        breed_id: u64,
+        owner: String,
+    },
+    BreedWithdrawn {
+        breed_id: u64,
+        owner: String,
+    },
+    // `breed_id` is unset for children minted directly through Mint
+    ChildMinted {
+        breed_id: Option<u64>,
+        child_contract_addr: String,
+        token_id: String,
+        owner: String,
+    },
+}
+
+impl BreedingHookMsg {
+    pub fn event(&self) -> HookEvent {
+        match self {
+            BreedingHookMsg::BreedStarted { .. } => HookEvent::BreedStarted,
+            BreedingHookMsg::BreedCancelled { .. } => HookEvent::BreedCancelled,
+            BreedingHookMsg::BreedWithdrawn { .. } => HookEvent::BreedWithdrawn,
+            BreedingHookMsg::ChildMinted { .. } => HookEvent::ChildMinted,
+        }
+    }
+
+    // Serializes the message wrapped for the hook contract
+    pub fn into_binary(self) -> StdResult<Binary> {
+        to_binary(&BreedingHookExecuteMsg::BreedingHook(self))
+    }
+
+    pub fn into_cosmos_msg(self, contract_addr: String) -> StdResult<CosmosMsg> {
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr,
+            msg: self.into_binary()?,
+            send: vec![],
+        }))
+    }
+}
+
+// Execute message shape hook contracts must accept
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BreedingHookExecuteMsg {
+    BreedingHook(BreedingHookMsg),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeDenomMsg {
+    Native(String),
+    Cw20(String),
+}
+
+// Royalty of the children minted from now on. `parent_share_bps` only applies to royalties paid
+// through PayRoyalty, RoyaltyInfo reports the whole royalty owed to `payment_address`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoyaltyMsg {
+    pub payment_address: String,
+    pub percentage: u64,
+    pub parent_share_bps: u16,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    pub breed_count_limit: u32,
+    pub breed_duration: u64,
+    pub breed_price_amount: Uint128,
+    pub breed_price_denom: String,
+    pub breed_price_cw20: Option<String>,
+    pub pricing: PricingMode,
+    pub child_contract_addr: Option<String>,
+    pub paused: bool,
+    pub max_batch_size: u32,
+    pub receipt_contract_addr: Option<String>,
+    pub child_token_id_scheme: ChildTokenIdScheme,
+    pub child_base_uri: Option<String>,
+    pub child_uri_template: Option<String>,
+    pub royalty: Option<RoyaltyMsg>,
+    pub owner: String,
+}
+
+// Data of the BreedBatch response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BreedBatchResponse {
+    pub breed_ids: Vec<u64>,
+}
+
+// Data of the WithdrawAll response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct WithdrawAllResponse {
+    pub breed_ids: Vec<u64>,
+    // More finished breeds are left for another call
+    pub has_more: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimableBreed {
+    pub breed_id: u64,
+    pub owner: String,
+    pub end_time: u64,
+    pub tip: Uint128,
+    pub denom: FeeDenomMsg,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimableBreedsResponse {
+    pub breeds: Vec<ClaimableBreed>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BreedStatsResponse {
+    pub height: u64,
+    pub breeds: u64,
+    pub children: u64,
+}
+
+// `address` is the parent collection and `token_id` the parent token on the parents leaderboard
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LeaderboardEntryResponse {
+    pub rank: u32,
+    pub address: String,
+    pub token_id: Option<String>,
+    pub score: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LeaderboardResponse {
+    pub entries: Vec<LeaderboardEntryResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ChildInfoResponse {
+    pub token_uri: Option<String>,
+    pub metadata: Option<Metadata>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PreviewChildUriResponse {
+    pub token_id: String,
+    pub token_uri: Option<String>,
+}
+
+// Same shape as the CW-2981 response, children without royalty are not found
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoyaltiesInfoResponse {
+    pub address: String,
+    pub royalty_amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CheckRoyaltiesResponse {
+    pub royalty_payments: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HookResponse {
+    pub addr: String,
+    pub events: Vec<HookEvent>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HooksResponse {
+    pub hooks: Vec<HookResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BreedInfoResponse {
+    // Set once the breed is withdrawn and its child minted
+    pub child_token_id: Option<String>,
+    pub end_time: u64,
+    pub nft_owner: String,
+    pub nft_token_id1: String,
+    pub nft_token_id2: String,
+    pub start_time: u64,
+    pub withdrawn: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct QueryBreedingsResponse {
+    pub breed_info: Vec<BreedInfoResponse>,
+}
+
+pub type BreedFinishedCountResponse = u32;
+pub type BreedRequestsCountResponse = u32;
+pub type BreededCountResponse = u32;
+pub type QueryBreedingsLengthResponse = u32;
+pub type QueryUserBreedingsLengthResponse = u32;
+pub type QueryUserBreedingsResponse = Vec<BreedInfoResponse>;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PayoutShareMsg {
+    pub recipient: String,
+    pub share_bps: u16,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PayoutTableResponse {
+    pub shares: Vec<PayoutShareMsg>,
+    pub dust_recipient: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TreasuryEntry {
+    pub denom: FeeDenomMsg,
+    pub earned: Uint128,
+    pub withdrawn: Uint128,
+    pub escrowed: Uint128,
+    pub tips: Uint128,
+    pub available: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TreasuryResponse {
+    pub entries: Vec<TreasuryEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CurrentPriceResponse {
+    pub amount: Uint128,
+    pub denom: FeeDenomMsg,
+    pub minted: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AllowlistStatusResponse {
+    pub round_id: Option<u64>,
+    pub eligible: bool,
+    pub discount_bps: u16,
+    pub quota: u32,
+    pub used: u32,
+    pub remaining: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ParentCollectionsResponse {
+    pub collections: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PairingResponse {
+    pub collection1: String,
+    pub collection2: String,
+    pub breed_price_amount: Uint128,
+    pub breed_duration: u64,
+    pub child_contract_addr: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PairingsResponse {
+    pub pairings: Vec<PairingResponse>,
+}
+
+// Metadata of a child, kept by this contract since cw721-base 0.6 tokens have no extension. The
+// name, description and image are also minted on the token.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct Metadata {
+    // Required when minting
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub image: Option<String>,
+    pub attributes: Option<Vec<Trait>>,
+    pub animation_url: Option<String>,
+    pub external_url: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Trait {
+    pub display_type: Option<String>,
+    pub trait_type: String,
+    pub value: String,
+}
@@ -0,0 +1,790 @@
+// proto.rs
+//
+// Protobuf encoding of the query responses, enabled by the `protobuf` feature and described by
+// proto/breeding.proto. The serde types in msg.rs stay the single source of truth: every
+// conversion below destructures the serde type exhaustively, so a field added there does not
+// compile until it is added here as well.
+// Addresses and amounts are encoded as strings, exactly like their JSON form.
+
+use crate::msg;
+use crate::state;
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ConfigResponse {
+    #[prost(uint32, tag = "1")]
+    pub breed_count_limit: u32,
+    #[prost(uint64, tag = "2")]
+    pub breed_duration: u64,
+    #[prost(string, tag = "3")]
+    pub breed_price_amount: String,
+    #[prost(string, tag = "4")]
+    pub breed_price_denom: String,
+    #[prost(string, optional, tag = "5")]
+    pub breed_price_cw20: Option<String>,
+    #[prost(message, optional, tag = "6")]
+    pub pricing: Option<PricingMode>,
+    #[prost(string, optional, tag = "7")]
+    pub child_contract_addr: Option<String>,
+    #[prost(string, tag = "8")]
+    pub owner: String,
+    #[prost(bool, tag = "9")]
+    pub paused: bool,
+    #[prost(uint32, tag = "10")]
+    pub max_batch_size: u32,
+    #[prost(string, optional, tag = "11")]
+    pub receipt_contract_addr: Option<String>,
+    #[prost(message, optional, tag = "12")]
+    pub child_token_id_scheme: Option<ChildTokenIdScheme>,
+    #[prost(string, optional, tag = "13")]
+    pub child_base_uri: Option<String>,
+    #[prost(string, optional, tag = "14")]
+    pub child_uri_template: Option<String>,
+    #[prost(message, optional, tag = "15")]
+    pub royalty: Option<Royalty>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Royalty {
+    #[prost(string, tag = "1")]
+    pub payment_address: String,
+    #[prost(uint64, tag = "2")]
+    pub percentage: u64,
+    #[prost(uint32, tag = "3")]
+    pub parent_share_bps: u32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PricingMode {
+    #[prost(oneof = "pricing_mode::Mode", tags = "1, 2, 3, 4")]
+    pub mode: Option<pricing_mode::Mode>,
+}
+
+pub mod pricing_mode {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Mode {
+        #[prost(message, tag = "1")]
+        Static(super::StaticPricing),
+        #[prost(message, tag = "2")]
+        Linear(super::LinearPricing),
+        #[prost(message, tag = "3")]
+        Exponential(super::ExponentialPricing),
+        #[prost(message, tag = "4")]
+        Stepped(super::SteppedPricing),
+    }
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StaticPricing {}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LinearPricing {
+    #[prost(string, tag = "1")]
+    pub increment: String,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExponentialPricing {
+    #[prost(uint32, tag = "1")]
+    pub growth_bps: u32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SteppedPricing {
+    #[prost(message, repeated, tag = "1")]
+    pub tiers: Vec<PriceTier>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PriceTier {
+    #[prost(uint64, tag = "1")]
+    pub min_minted: u64,
+    #[prost(string, tag = "2")]
+    pub amount: String,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ChildTokenIdScheme {
+    #[prost(oneof = "child_token_id_scheme::Scheme", tags = "1, 2, 3")]
+    pub scheme: Option<child_token_id_scheme::Scheme>,
+}
+
+pub mod child_token_id_scheme {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Scheme {
+        #[prost(message, tag = "1")]
+        Sequential(super::SequentialTokenIds),
+        #[prost(message, tag = "2")]
+        Padded(super::PaddedTokenIds),
+        #[prost(message, tag = "3")]
+        Hash(super::HashTokenIds),
+    }
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SequentialTokenIds {
+    #[prost(string, tag = "1")]
+    pub prefix: String,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PaddedTokenIds {
+    #[prost(uint32, tag = "1")]
+    pub width: u32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HashTokenIds {}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BreedInfoResponse {
+    #[prost(string, optional, tag = "1")]
+    pub child_token_id: Option<String>,
+    #[prost(uint64, tag = "2")]
+    pub end_time: u64,
+    #[prost(string, tag = "3")]
+    pub nft_owner: String,
+    #[prost(string, tag = "4")]
+    pub nft_token_id1: String,
+    #[prost(string, tag = "5")]
+    pub nft_token_id2: String,
+    #[prost(uint64, tag = "6")]
+    pub start_time: u64,
+    #[prost(bool, tag = "7")]
+    pub withdrawn: bool,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryBreedingsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub breed_info: Vec<BreedInfoResponse>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CountResponse {
+    #[prost(uint32, tag = "1")]
+    pub count: u32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BreedStatsResponse {
+    #[prost(uint64, tag = "1")]
+    pub height: u64,
+    #[prost(uint64, tag = "2")]
+    pub breeds: u64,
+    #[prost(uint64, tag = "3")]
+    pub children: u64,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PayoutTableResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub shares: Vec<PayoutShare>,
+    #[prost(string, tag = "2")]
+    pub dust_recipient: String,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PayoutShare {
+    #[prost(string, tag = "1")]
+    pub recipient: String,
+    #[prost(uint32, tag = "2")]
+    pub share_bps: u32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FeeDenom {
+    #[prost(oneof = "fee_denom::Denom", tags = "1, 2")]
+    pub denom: Option<fee_denom::Denom>,
+}
+
+pub mod fee_denom {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Denom {
+        #[prost(string, tag = "1")]
+        Native(String),
+        #[prost(string, tag = "2")]
+        Cw20(String),
+    }
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TreasuryEntry {
+    #[prost(message, optional, tag = "1")]
+    pub denom: Option<FeeDenom>,
+    #[prost(string, tag = "2")]
+    pub earned: String,
+    #[prost(string, tag = "3")]
+    pub withdrawn: String,
+    #[prost(string, tag = "4")]
+    pub escrowed: String,
+    #[prost(string, tag = "5")]
+    pub available: String,
+    #[prost(string, tag = "6")]
+    pub tips: String,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TreasuryResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub entries: Vec<TreasuryEntry>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CurrentPriceResponse {
+    #[prost(string, tag = "1")]
+    pub amount: String,
+    #[prost(message, optional, tag = "2")]
+    pub denom: Option<FeeDenom>,
+    #[prost(uint64, tag = "3")]
+    pub minted: u64,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AllowlistStatusResponse {
+    #[prost(uint64, optional, tag = "1")]
+    pub round_id: Option<u64>,
+    #[prost(bool, tag = "2")]
+    pub eligible: bool,
+    #[prost(uint32, tag = "3")]
+    pub discount_bps: u32,
+    #[prost(uint32, tag = "4")]
+    pub quota: u32,
+    #[prost(uint32, tag = "5")]
+    pub used: u32,
+    #[prost(uint32, tag = "6")]
+    pub remaining: u32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ParentCollectionsResponse {
+    #[prost(string, repeated, tag = "1")]
+    pub collections: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PairingResponse {
+    #[prost(string, tag = "1")]
+    pub collection1: String,
+    #[prost(string, tag = "2")]
+    pub collection2: String,
+    #[prost(string, tag = "3")]
+    pub breed_price_amount: String,
+    #[prost(uint64, tag = "4")]
+    pub breed_duration: u64,
+    #[prost(string, optional, tag = "5")]
+    pub child_contract_addr: Option<String>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PairingsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub pairings: Vec<PairingResponse>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ClaimableBreed {
+    #[prost(uint64, tag = "1")]
+    pub breed_id: u64,
+    #[prost(string, tag = "2")]
+    pub owner: String,
+    #[prost(uint64, tag = "3")]
+    pub end_time: u64,
+    #[prost(string, tag = "4")]
+    pub tip: String,
+    #[prost(message, optional, tag = "5")]
+    pub denom: Option<FeeDenom>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ClaimableBreedsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub breeds: Vec<ClaimableBreed>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LeaderboardEntry {
+    #[prost(uint32, tag = "1")]
+    pub rank: u32,
+    #[prost(string, tag = "2")]
+    pub address: String,
+    #[prost(string, optional, tag = "3")]
+    pub token_id: Option<String>,
+    #[prost(string, tag = "4")]
+    pub score: String,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LeaderboardResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PreviewChildUriResponse {
+    #[prost(string, tag = "1")]
+    pub token_id: String,
+    #[prost(string, optional, tag = "2")]
+    pub token_uri: Option<String>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Trait {
+    #[prost(string, optional, tag = "1")]
+    pub display_type: Option<String>,
+    #[prost(string, tag = "2")]
+    pub trait_type: String,
+    #[prost(string, tag = "3")]
+    pub value: String,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Metadata {
+    #[prost(string, optional, tag = "1")]
+    pub name: Option<String>,
+    #[prost(string, optional, tag = "2")]
+    pub description: Option<String>,
+    #[prost(string, optional, tag = "3")]
+    pub image: Option<String>,
+    #[prost(message, repeated, tag = "4")]
+    pub attributes: Vec<Trait>,
+    #[prost(string, optional, tag = "5")]
+    pub animation_url: Option<String>,
+    #[prost(string, optional, tag = "6")]
+    pub external_url: Option<String>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ChildInfoResponse {
+    #[prost(string, optional, tag = "1")]
+    pub token_uri: Option<String>,
+    #[prost(message, optional, tag = "2")]
+    pub metadata: Option<Metadata>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RoyaltiesInfoResponse {
+    #[prost(string, tag = "1")]
+    pub address: String,
+    #[prost(string, tag = "2")]
+    pub royalty_amount: String,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CheckRoyaltiesResponse {
+    #[prost(bool, tag = "1")]
+    pub royalty_payments: bool,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HookResponse {
+    #[prost(string, tag = "1")]
+    pub addr: String,
+    #[prost(string, repeated, tag = "2")]
+    pub events: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HooksResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub hooks: Vec<HookResponse>,
+}
+
+impl From<msg::ConfigResponse> for ConfigResponse {
+    fn from(response: msg::ConfigResponse) -> Self {
+        let msg::ConfigResponse {
+            breed_count_limit,
+            breed_duration,
+            breed_price_amount,
+            breed_price_denom,
+            breed_price_cw20,
+            pricing,
+            child_contract_addr,
+            paused,
+            max_batch_size,
+            receipt_contract_addr,
+            child_token_id_scheme,
+            child_base_uri,
+            child_uri_template,
+            royalty,
+            owner,
+        } = response;
+        ConfigResponse {
+            breed_count_limit,
+            breed_duration,
+            breed_price_amount: breed_price_amount.to_string(),
+            breed_price_denom,
+            breed_price_cw20: breed_price_cw20.map(|addr| addr.to_string()),
+            pricing: Some(pricing.into()),
+            child_contract_addr: child_contract_addr.map(|addr| addr.to_string()),
+            owner: owner.to_string(),
+            paused,
+            max_batch_size,
+            receipt_contract_addr: receipt_contract_addr.map(|addr| addr.to_string()),
+            child_token_id_scheme: Some(child_token_id_scheme.into()),
+            child_base_uri,
+            child_uri_template,
+            royalty: royalty.map(|msg::RoyaltyMsg { payment_address, percentage, parent_share_bps }| Royalty {
+                payment_address: payment_address.to_string(),
+                percentage,
+                parent_share_bps: parent_share_bps as u32,
+            }),
+        }
+    }
+}
+
+impl From<state::PricingMode> for PricingMode {
+    fn from(pricing: state::PricingMode) -> Self {
+        let mode = match pricing {
+            state::PricingMode::Static {} => pricing_mode::Mode::Static(StaticPricing {}),
+            state::PricingMode::Linear { increment } => pricing_mode::Mode::Linear(LinearPricing {
+                increment: increment.to_string(),
+            }),
+            state::PricingMode::Exponential { growth_bps } => {
+                pricing_mode::Mode::Exponential(ExponentialPricing { growth_bps })
+            }
+            state::PricingMode::Stepped { tiers } => pricing_mode::Mode::Stepped(SteppedPricing {
+                tiers: tiers
+                    .into_iter()
+                    .map(|state::PriceTier { min_minted, amount }| PriceTier {
+                        min_minted,
+                        amount: amount.to_string(),
+                    })
+                    .collect(),
+            }),
+        };
+        PricingMode { mode: Some(mode) }
+    }
+}
+
+impl From<state::ChildTokenIdScheme> for ChildTokenIdScheme {
+    fn from(scheme: state::ChildTokenIdScheme) -> Self {
+        let scheme = match scheme {
+            state::ChildTokenIdScheme::Sequential { prefix } => {
+                child_token_id_scheme::Scheme::Sequential(SequentialTokenIds { prefix })
+            }
+            state::ChildTokenIdScheme::Padded { width } => child_token_id_scheme::Scheme::Padded(PaddedTokenIds {
+                width: width as u32,
+            }),
+            state::ChildTokenIdScheme::Hash {} => child_token_id_scheme::Scheme::Hash(HashTokenIds {}),
+        };
+        ChildTokenIdScheme { scheme: Some(scheme) }
+    }
+}
+
+impl From<msg::BreedInfoResponse> for BreedInfoResponse {
+    fn from(response: msg::BreedInfoResponse) -> Self {
+        let msg::BreedInfoResponse {
+            child_token_id,
+            end_time,
+            nft_owner,
+            nft_token_id1,
+            nft_token_id2,
+            start_time,
+            withdrawn,
+        } = response;
+        BreedInfoResponse {
+            child_token_id,
+            end_time,
+            nft_owner: nft_owner.to_string(),
+            nft_token_id1,
+            nft_token_id2,
+            start_time,
+            withdrawn,
+        }
+    }
+}
+
+impl From<msg::QueryBreedingsResponse> for QueryBreedingsResponse {
+    fn from(response: msg::QueryBreedingsResponse) -> Self {
+        let msg::QueryBreedingsResponse { breed_info } = response;
+        QueryBreedingsResponse {
+            breed_info: breed_info.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+// Count queries answer a bare number in JSON
+impl From<u32> for CountResponse {
+    fn from(count: u32) -> Self {
+        CountResponse { count }
+    }
+}
+
+impl From<msg::BreedStatsResponse> for BreedStatsResponse {
+    fn from(response: msg::BreedStatsResponse) -> Self {
+        let msg::BreedStatsResponse {
+            height,
+            breeds,
+            children,
+        } = response;
+        BreedStatsResponse {
+            height,
+            breeds,
+            children,
+        }
+    }
+}
+
+// A user's breedings are a bare list in JSON and share the QueryBreedingsResponse message
+impl From<msg::QueryUserBreedingsResponse> for QueryBreedingsResponse {
+    fn from(breed_info: msg::QueryUserBreedingsResponse) -> Self {
+        QueryBreedingsResponse {
+            breed_info: breed_info.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<msg::PayoutTableResponse> for PayoutTableResponse {
+    fn from(response: msg::PayoutTableResponse) -> Self {
+        let msg::PayoutTableResponse {
+            shares,
+            dust_recipient,
+        } = response;
+        PayoutTableResponse {
+            shares: shares
+                .into_iter()
+                .map(|msg::PayoutShareMsg { recipient, share_bps }| PayoutShare {
+                    recipient: recipient.to_string(),
+                    share_bps: share_bps as u32,
+                })
+                .collect(),
+            dust_recipient: dust_recipient.to_string(),
+        }
+    }
+}
+
+impl From<msg::FeeDenomMsg> for FeeDenom {
+    fn from(denom: msg::FeeDenomMsg) -> Self {
+        let denom = match denom {
+            msg::FeeDenomMsg::Native(denom) => fee_denom::Denom::Native(denom),
+            msg::FeeDenomMsg::Cw20(token) => fee_denom::Denom::Cw20(token.to_string()),
+        };
+        FeeDenom { denom: Some(denom) }
+    }
+}
+
+impl From<msg::TreasuryResponse> for TreasuryResponse {
+    fn from(response: msg::TreasuryResponse) -> Self {
+        let msg::TreasuryResponse { entries } = response;
+        TreasuryResponse {
+            entries: entries
+                .into_iter()
+                .map(|entry| {
+                    let msg::TreasuryEntry {
+                        denom,
+                        earned,
+                        withdrawn,
+                        escrowed,
+                        tips,
+                        available,
+                    } = entry;
+                    TreasuryEntry {
+                        denom: Some(denom.into()),
+                        earned: earned.to_string(),
+                        withdrawn: withdrawn.to_string(),
+                        escrowed: escrowed.to_string(),
+                        available: available.to_string(),
+                        tips: tips.to_string(),
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<msg::CurrentPriceResponse> for CurrentPriceResponse {
+    fn from(response: msg::CurrentPriceResponse) -> Self {
+        let msg::CurrentPriceResponse {
+            amount,
+            denom,
+            minted,
+        } = response;
+        CurrentPriceResponse {
+            amount: amount.to_string(),
+            denom: Some(denom.into()),
+            minted,
+        }
+    }
+}
+
+impl From<msg::AllowlistStatusResponse> for AllowlistStatusResponse {
+    fn from(response: msg::AllowlistStatusResponse) -> Self {
+        let msg::AllowlistStatusResponse {
+            round_id,
+            eligible,
+            discount_bps,
+            quota,
+            used,
+            remaining,
+        } = response;
+        AllowlistStatusResponse {
+            round_id,
+            eligible,
+            discount_bps: discount_bps as u32,
+            quota,
+            used,
+            remaining,
+        }
+    }
+}
+
+impl From<msg::ParentCollectionsResponse> for ParentCollectionsResponse {
+    fn from(response: msg::ParentCollectionsResponse) -> Self {
+        let msg::ParentCollectionsResponse { collections } = response;
+        ParentCollectionsResponse {
+            collections: collections.into_iter().map(|addr| addr.to_string()).collect(),
+        }
+    }
+}
+
+impl From<msg::PairingsResponse> for PairingsResponse {
+    fn from(response: msg::PairingsResponse) -> Self {
+        let msg::PairingsResponse { pairings } = response;
+        PairingsResponse {
+            pairings: pairings
+                .into_iter()
+                .map(|pairing| {
+                    let msg::PairingResponse {
+                        collection1,
+                        collection2,
+                        breed_price_amount,
+                        breed_duration,
+                        child_contract_addr,
+                    } = pairing;
+                    PairingResponse {
+                        collection1: collection1.to_string(),
+                        collection2: collection2.to_string(),
+                        breed_price_amount: breed_price_amount.to_string(),
+                        breed_duration,
+                        child_contract_addr: child_contract_addr.map(|addr| addr.to_string()),
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<msg::ClaimableBreedsResponse> for ClaimableBreedsResponse {
+    fn from(response: msg::ClaimableBreedsResponse) -> Self {
+        let msg::ClaimableBreedsResponse { breeds } = response;
+        ClaimableBreedsResponse {
+            breeds: breeds
+                .into_iter()
+                .map(|breed| {
+                    let msg::ClaimableBreed {
+                        breed_id,
+                        owner,
+                        end_time,
+                        tip,
+                        denom,
+                    } = breed;
+                    ClaimableBreed {
+                        breed_id,
+                        owner: owner.to_string(),
+                        end_time,
+                        tip: tip.to_string(),
+                        denom: Some(denom.into()),
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<msg::HooksResponse> for HooksResponse {
+    fn from(response: msg::HooksResponse) -> Self {
+        let msg::HooksResponse { hooks } = response;
+        HooksResponse {
+            hooks: hooks
+                .into_iter()
+                .map(|hook| {
+                    let msg::HookResponse { addr, events } = hook;
+                    HookResponse {
+                        addr: addr.to_string(),
+                        events: events.iter().map(|event| event.as_str().to_string()).collect(),
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<msg::LeaderboardResponse> for LeaderboardResponse {
+    fn from(response: msg::LeaderboardResponse) -> Self {
+        let msg::LeaderboardResponse { entries } = response;
+        LeaderboardResponse {
+            entries: entries
+                .into_iter()
+                .map(|entry| {
+                    let msg::LeaderboardEntryResponse {
+                        rank,
+                        address,
+                        token_id,
+                        score,
+                    } = entry;
+                    LeaderboardEntry {
+                        rank,
+                        address: address.to_string(),
+                        token_id,
+                        score: score.to_string(),
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<msg::PreviewChildUriResponse> for PreviewChildUriResponse {
+    fn from(response: msg::PreviewChildUriResponse) -> Self {
+        let msg::PreviewChildUriResponse { token_id, token_uri } = response;
+        PreviewChildUriResponse { token_id, token_uri }
+    }
+}
+
+impl From<msg::Metadata> for Metadata {
+    fn from(metadata: msg::Metadata) -> Self {
+        let msg::Metadata { name, description, image, attributes, animation_url, external_url } = metadata;
+        Metadata {
+            name,
+            description,
+            image,
+            attributes: attributes
+                .unwrap_or_default()
+                .into_iter()
+                .map(|attribute| {
+                    let msg::Trait { display_type, trait_type, value } = attribute;
+                    Trait { display_type, trait_type, value }
+                })
+                .collect(),
+            animation_url,
+            external_url,
+        }
+    }
+}
+
+impl From<msg::ChildInfoResponse> for ChildInfoResponse {
+    fn from(response: msg::ChildInfoResponse) -> Self {
+        let msg::ChildInfoResponse { token_uri, metadata } = response;
+        ChildInfoResponse {
+            token_uri,
+            metadata: metadata.map(Metadata::from),
+        }
+    }
+}
+
+impl From<msg::RoyaltiesInfoResponse> for RoyaltiesInfoResponse {
+    fn from(response: msg::RoyaltiesInfoResponse) -> Self {
+        let msg::RoyaltiesInfoResponse { address, royalty_amount } = response;
+        RoyaltiesInfoResponse {
+            address,
+            royalty_amount: royalty_amount.to_string(),
+        }
+    }
+}
+
+impl From<msg::CheckRoyaltiesResponse> for CheckRoyaltiesResponse {
+    fn from(response: msg::CheckRoyaltiesResponse) -> Self {
+        let msg::CheckRoyaltiesResponse { royalty_payments } = response;
+        CheckRoyaltiesResponse { royalty_payments }
+    }
+}
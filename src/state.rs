@@ -1,17 +1,123 @@
-use cosmwasm_std::{CanonicalAddr, Uint128};
-use cw721_base::ContractInfo;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{CanonicalAddr, Coin, Uint128};
+
+use crate::msg::Metadata;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
     pub breed_count_limit: u32,
+    // Defaults for pairings registered without their own duration or price
     pub breed_duration: u64,
     pub breed_price_amount: Uint128,
     pub breed_price_denom: String,
+    pub breed_price_cw20: Option<CanonicalAddr>,
+    #[serde(default)]
+    pub pricing: PricingMode,
+    // Default child collection, set once its instantiation reply comes back
+    pub child_contract_addr: Option<CanonicalAddr>,
+    // Set by governance, blocks new breeds and mints while withdrawals keep working
+    #[serde(default)]
+    pub paused: bool,
+    // Largest number of pairs accepted by a single BreedBatch
+    #[serde(default = "default_max_batch_size")]
+    pub max_batch_size: u32,
+    // Collection of breed receipts, breeds started while it is set are held through their receipt
+    #[serde(default)]
+    pub receipt_contract_addr: Option<CanonicalAddr>,
+    // How token ids of minted children are generated
+    #[serde(default)]
+    pub child_token_id_scheme: ChildTokenIdScheme,
+    // Children get a token uri only when a template is set, `{base}` is replaced by the base uri
+    // and `{id}` by the child token id
+    #[serde(default)]
+    pub child_base_uri: Option<String>,
+    #[serde(default)]
+    pub child_uri_template: Option<String>,
+    // CW-2981 royalty of minted children, none when unset
+    #[serde(default)]
+    pub royalty: Option<RoyaltyConfig>,
+}
+
+pub const DEFAULT_MAX_BATCH_SIZE: u32 = 20;
+
+fn default_max_batch_size() -> u32 {
+    DEFAULT_MAX_BATCH_SIZE
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PricingMode {
+    // Always `breed_price_amount`
+    Static {},
+    // `breed_price_amount + increment * minted`
+    Linear { increment: Uint128 },
+    // `breed_price_amount * (1 + growth_bps / 10000) ^ minted`
+    Exponential { growth_bps: u32 },
+    // Amount of the highest tier reached by `minted`, `breed_price_amount` below the first tier
+    Stepped { tiers: Vec<PriceTier> },
+}
+
+impl Default for PricingMode {
+    fn default() -> Self {
+        PricingMode::Static {}
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChildTokenIdScheme {
+    // `{prefix}{id}`
+    Sequential { prefix: String },
+    // `id` left-padded with zeros to `width` digits
+    Padded { width: u8 },
+    // Hex sha256 of `{token_id1}:{token_id2}:{id}`, parents are empty for direct mints
+    Hash {},
+}
+
+impl Default for ChildTokenIdScheme {
+    fn default() -> Self {
+        ChildTokenIdScheme::Sequential { prefix: String::new() }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoyaltyConfig {
+    pub payment_address: CanonicalAddr,
+    // Percentage of the sale price, as in CW-2981
+    pub percentage: u64,
+    // Part of the royalty of bred children routed to the owner of their parents at breed time
+    pub parent_share_bps: u16,
+}
+
+// Token uri and metadata of a minted child, the child collection only keeps the name, description
+// and image of the metadata
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ChildInfo {
+    pub token_uri: Option<String>,
+    pub metadata: Option<Metadata>,
+}
+
+// Royalty a child was minted with. CW-2981 payments go to the payment address, the parent share is
+// only split from royalties paid through PayRoyalty.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ChildRoyalty {
+    pub payment_address: CanonicalAddr,
+    pub percentage: u64,
+    pub parent_owner: Option<CanonicalAddr>,
+    pub parent_share_bps: u16,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PriceTier {
+    pub min_minted: u64,
+    pub amount: Uint128,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ConfigState {
-    pub config: Option<Config>,
+    pub config: Config,
     pub owner: CanonicalAddr,
 }
 
@@ -19,29 +125,204 @@ pub struct ConfigState {
 pub struct BreedCount {
     pub count: u64,
     pub latest_id: u64,
+    // Every child minted, by a finished breed or directly by the owner
+    pub minted: u64,
+    #[serde(default)]
+    pub direct_minted: u64,
+}
+
+// Breeds started and children received, checkpointed per block height for the global totals and
+// for each address
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct BreedStats {
+    pub breeds: u64,
+    pub children: u64,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Breed {
     pub id: u64,
     pub owner: CanonicalAddr,
+    pub nft_contract_addr1: CanonicalAddr,
     pub nft_token_id1: String,
+    pub nft_contract_addr2: CanonicalAddr,
     pub nft_token_id2: String,
+    pub child_contract_addr: CanonicalAddr,
     pub start_time: u64,
     pub end_time: u64,
     pub is_withdrawn: bool,
+    pub fee_denom: FeeDenom,
+    pub fee_amount: Uint128,
+    // Tip paid to whoever claims the breed once finished, `None` when the breeder did not opt in
+    #[serde(default)]
+    pub auto_claim_tip: Option<Uint128>,
+    // Receipt collection the breed was tokenised on, the receipt token id is the breed id
+    #[serde(default)]
+    pub receipt_contract_addr: Option<CanonicalAddr>,
+    // Token id the child was minted with, breeds withdrawn before it was recorded used their id
+    #[serde(default)]
+    pub child_token_id: Option<String>,
+    // Address that started the breed and owned both parents then, unlike `owner` it survives
+    // transfers of the breed
+    #[serde(default)]
+    pub breeder: Option<CanonicalAddr>,
+    // Allowlist round whose quota the breed used, given back when the breed is cancelled
+    #[serde(default)]
+    pub allowlist_round: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PayoutShare {
+    pub recipient: CanonicalAddr,
+    pub share_bps: u16,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PayoutTable {
+    pub shares: Vec<PayoutShare>,
+    pub dust_recipient: CanonicalAddr,
 }
 
-impl Breed {
-    pub fn new(id: u64, owner: CanonicalAddr, nft_token_id1: String, nft_token_id2: String, start_time: u64, end_time: u64, is_withdrawn: bool) -> Self {
-        Breed {
-            id,
-            owner,
-            nft_token_id1,
-            nft_token_id2,
-            start_time,
-            end_time,
-            is_withdrawn,
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeDenom {
+    Native(String),
+    Cw20(CanonicalAddr),
+}
+
+impl FeeDenom {
+    // Storage key of the fee ledger entry for this denom
+    pub fn key(&self) -> Vec<u8> {
+        match self {
+            FeeDenom::Native(denom) => [b"native:".as_ref(), denom.as_bytes()].concat(),
+            FeeDenom::Cw20(addr) => [b"cw20:".as_ref(), addr.as_slice()].concat(),
         }
     }
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaderboardKind {
+    Breeders,
+    Parents,
+    Spenders(FeeDenom),
+}
+
+impl LeaderboardKind {
+    // Storage namespace of this leaderboard
+    pub fn key(&self) -> Vec<u8> {
+        match self {
+            LeaderboardKind::Breeders => b"breeders".to_vec(),
+            LeaderboardKind::Parents => b"parents".to_vec(),
+            LeaderboardKind::Spenders(denom) => [b"spenders:".as_ref(), &denom.key()].concat(),
+        }
+    }
+}
+
+// A ranked address, or a parent token of the `address` collection when `token_id` is set
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LeaderboardEntry {
+    pub address: CanonicalAddr,
+    pub token_id: Option<String>,
+    pub score: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeeLedger {
+    pub denom: FeeDenom,
+    pub earned: Uint128,
+    pub withdrawn: Uint128,
+    pub escrowed: Uint128,
+    // Auto-claim tips held for keepers, never part of the earned fees
+    #[serde(default)]
+    pub tips: Uint128,
+}
+
+impl FeeLedger {
+    pub fn new(denom: FeeDenom) -> Self {
+        FeeLedger {
+            denom,
+            earned: Uint128::zero(),
+            withdrawn: Uint128::zero(),
+            escrowed: Uint128::zero(),
+            tips: Uint128::zero(),
+        }
+    }
+
+    // Earned fees that have not been withdrawn yet
+    pub fn available(&self) -> Uint128 {
+        self.earned.checked_sub(self.withdrawn).unwrap_or_default()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AllowlistRound {
+    pub id: u64,
+    // Hex encoded sha256 Merkle root of the snapshot
+    pub merkle_root: String,
+    // 10000 makes breeding free
+    pub discount_bps: u16,
+    // Discounted breeds allowed per address in this round
+    pub quota: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ParentCollection {
+    pub addr: CanonicalAddr,
+}
+
+// Pending sale of a breed position to one recipient, stale once the breed changes owner
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BreedOffer {
+    pub breed_id: u64,
+    pub owner: CanonicalAddr,
+    pub recipient: CanonicalAddr,
+    pub price: Option<Coin>,
+}
+
+// Collections whose tokens may breed together, in either order
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Pairing {
+    pub collection1: CanonicalAddr,
+    pub collection2: CanonicalAddr,
+    pub breed_price_amount: Uint128,
+    pub breed_duration: u64,
+    // Children go to the default child collection when unset
+    pub child_contract_addr: Option<CanonicalAddr>,
+}
+
+// Where the address of a child collection being instantiated gets stored
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChildCollectionTarget {
+    Default,
+    Pairing(CanonicalAddr, CanonicalAddr),
+    Receipts,
+}
+
+// Breeding events a hook contract can subscribe to
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HookEvent {
+    BreedStarted,
+    BreedCancelled,
+    BreedWithdrawn,
+    ChildMinted,
+}
+
+impl HookEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HookEvent::BreedStarted => "breed_started",
+            HookEvent::BreedCancelled => "breed_cancelled",
+            HookEvent::BreedWithdrawn => "breed_withdrawn",
+            HookEvent::ChildMinted => "child_minted",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Hook {
+    pub addr: CanonicalAddr,
+    pub events: Vec<HookEvent>,
+}
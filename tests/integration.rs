@@ -0,0 +1,1792 @@
+// integration.rs
+//
+// Drives the breeding contract against real cw721-base parent and child collections and a bank
+// holding test balances.
+
+use cosmwasm_std::testing::{mock_env, MockApi, MockStorage};
+use cosmwasm_std::{coin, coins, from_binary, to_binary, Addr, Coin, Empty, StdResult, Uint128};
+use cw721::{Cw721QueryMsg, OwnerOfResponse};
+use cw721_base::msg::{ExecuteMsg as NftExecuteMsg, InstantiateMsg as NftInstantiateMsg, MintMsg};
+use cw_multi_test::{App, Contract, ContractWrapper, SimpleBank};
+use sha2::{Digest, Sha256};
+
+use my_contract::contract::{execute, instantiate, query, sudo};
+use my_contract::error::ContractError;
+use my_contract::msg::{
+    BreedBatchResponse, BreedInfoResponse, BreedStatsResponse, CheckRoyaltiesResponse,
+    ChildCollectionMsg, ChildInfoResponse, ClaimableBreedsResponse, ConfigResponse, CurrentPriceResponse, FeeDenomMsg, HandleMsg, InitMsg,
+    LeaderboardKindMsg, LeaderboardResponse, Metadata, ParentNft, PayoutShareMsg,
+    PreviewChildUriResponse, QueryMsg, ReceiptMsg, RoyaltiesInfoResponse, RoyaltyMsg, SudoMsg, Trait,
+    TreasuryResponse, WithdrawAllResponse,
+};
+use my_contract::state::{ChildTokenIdScheme, HookEvent, PricingMode};
+
+const OWNER: &str = "owner";
+const BREEDER: &str = "breeder";
+const STRANGER: &str = "stranger";
+const DENOM: &str = "uusd";
+const BREED_PRICE: u128 = 1_000;
+const BREED_DURATION: u64 = 3_600;
+
+struct Suite {
+    app: App,
+    breeding: Addr,
+    parent1: Addr,
+    parent2: Addr,
+    child: Addr,
+}
+
+fn mock_app() -> App {
+    let env = mock_env();
+    let api = Box::new(MockApi::default());
+    let bank = SimpleBank {};
+    App::new(api, env.block, bank, || Box::new(MockStorage::new()))
+}
+
+fn contract_breeding() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new_with_sudo(execute, instantiate, query, sudo);
+    Box::new(contract)
+}
+
+fn contract_cw721() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        cw721_base::contract::execute,
+        cw721_base::contract::instantiate,
+        cw721_base::contract::query,
+    );
+    Box::new(contract)
+}
+
+fn init_msg() -> InitMsg {
+    InitMsg {
+        breed_count_limit: 10,
+        breed_duration: BREED_DURATION,
+        breed_price_amount: Uint128::from(BREED_PRICE),
+        breed_price_denom: DENOM.to_string(),
+        breed_price_cw20: None,
+        pricing: None,
+        max_batch_size: None,
+        child_token_id_scheme: None,
+        child_base_uri: None,
+        child_uri_template: None,
+        royalty: None,
+        child_collection: None,
+    }
+}
+
+// Deploys an empty collection `minter` can mint into
+fn minted_collection(app: &mut App, code_id: u64, minter: &Addr, name: &str) -> Addr {
+    app.instantiate_contract(
+        code_id,
+        Addr::unchecked(OWNER),
+        &NftInstantiateMsg {
+            name: name.to_string(),
+            symbol: name.to_uppercase(),
+            minter: minter.to_string(),
+        },
+        &[],
+        name,
+    )
+    .unwrap()
+}
+
+// Governance config update changing only the given collections
+fn collections_msg(child: Option<&Addr>, receipts: Option<&Addr>) -> SudoMsg {
+    SudoMsg::UpdateConfig {
+        breed_count_limit: None,
+        breed_duration: None,
+        breed_price_amount: None,
+        breed_price_denom: None,
+        breed_price_cw20: None,
+        pricing: None,
+        max_batch_size: None,
+        child_token_id_scheme: None,
+        child_base_uri: None,
+        child_uri_template: None,
+        royalty: None,
+        child_contract_addr: child.map(Addr::to_string),
+        receipt_contract_addr: receipts.map(Addr::to_string),
+        paused: None,
+    }
+}
+
+fn parent_collection(app: &mut App, code_id: u64, name: &str, tokens: &[&str]) -> Addr {
+    let collection = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked(OWNER),
+            &NftInstantiateMsg {
+                name: name.to_string(),
+                symbol: name.to_uppercase(),
+                minter: OWNER.to_string(),
+            },
+            &[],
+            name,
+        )
+        .unwrap();
+    for token_id in tokens {
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            collection.clone(),
+            &NftExecuteMsg::Mint(MintMsg {
+                token_id: token_id.to_string(),
+                owner: BREEDER.to_string(),
+                name: token_id.to_string(),
+                description: None,
+                image: None,
+            }),
+            &[],
+        )
+        .unwrap();
+    }
+    collection
+}
+
+// Deploys the breeding contract with its child collection, two paired parent collections and a
+// funded breeder
+fn setup() -> Suite {
+    let mut app = mock_app();
+    app.set_bank_balance(&Addr::unchecked(BREEDER), coins(10 * BREED_PRICE, DENOM))
+        .unwrap();
+    app.set_bank_balance(&Addr::unchecked(STRANGER), coins(10 * BREED_PRICE, DENOM))
+        .unwrap();
+
+    let breeding_code_id = app.store_code(contract_breeding());
+    let cw721_code_id = app.store_code(contract_cw721());
+
+    let parent1 = parent_collection(&mut app, cw721_code_id, "cats", &["cat1", "cat2", "cat3"]);
+    let parent2 = parent_collection(&mut app, cw721_code_id, "dogs", &["dog1", "dog2", "dog3"]);
+
+    let breeding = app
+        .instantiate_contract(breeding_code_id, Addr::unchecked(OWNER), &init_msg(), &[], "breeding")
+        .unwrap();
+
+    // cw-multi-test 0.6 drops submessages, so the child collection cannot come from the instantiate
+    // reply and is deployed here and handed over by governance instead
+    let child = minted_collection(&mut app, cw721_code_id, &breeding, "children");
+    app.sudo(breeding.clone(), &collections_msg(Some(&child), None)).unwrap();
+
+    let mut suite = Suite {
+        app,
+        breeding,
+        parent1,
+        parent2,
+        child,
+    };
+    for collection in [suite.parent1.clone(), suite.parent2.clone()].iter() {
+        suite
+            .execute(OWNER, HandleMsg::AddParentCollection { addr: collection.to_string() }, &[])
+            .unwrap();
+    }
+    suite
+        .execute(
+            OWNER,
+            HandleMsg::SetPairing {
+                collection1: suite.parent1.to_string(),
+                collection2: suite.parent2.to_string(),
+                breed_price_amount: None,
+                breed_duration: None,
+                child_contract_addr: None,
+            },
+            &[],
+        )
+        .unwrap();
+    suite
+}
+
+fn leaf_hash(address: &str) -> String {
+    hex::encode(Sha256::digest(address.as_bytes()))
+}
+
+impl Suite {
+    fn execute(&mut self, sender: &str, msg: HandleMsg, funds: &[Coin]) -> Result<(), String> {
+        self.app
+            .execute_contract(Addr::unchecked(sender), self.breeding.clone(), &msg, funds)
+            .map(|_| ())
+    }
+
+    fn sudo(&mut self, msg: SudoMsg) -> Result<(), String> {
+        self.app.sudo(self.breeding.clone(), &msg).map(|_| ())
+    }
+
+    // Deploys an empty collection the breeding contract can mint into
+    fn minted_collection(&mut self, name: &str) -> Addr {
+        let cw721_code_id = self.app.store_code(contract_cw721());
+        minted_collection(&mut self.app, cw721_code_id, &self.breeding, name)
+    }
+
+    fn execute_nft(&mut self, sender: &str, collection: &Addr, msg: &NftExecuteMsg) {
+        self.app
+            .execute_contract(Addr::unchecked(sender), collection.clone(), msg, &[])
+            .unwrap();
+    }
+
+    // Sends the receipt of a breed to the breeding contract along with `msg`
+    fn send_receipt(&mut self, sender: &str, receipts: &Addr, breed_id: u64, msg: Option<ReceiptMsg>) -> Result<(), String> {
+        let msg = NftExecuteMsg::SendNft {
+            contract: self.breeding.to_string(),
+            token_id: breed_id.to_string(),
+            msg: msg.map(|msg| to_binary(&msg).unwrap()),
+        };
+        self.app
+            .execute_contract(Addr::unchecked(sender), receipts.clone(), &msg, &[])
+            .map(|_| ())
+    }
+
+    // Lets the breeding contract pull both parents into escrow
+    fn approve_parents(&mut self, token1: &str, token2: &str) {
+        let (parent1, parent2) = (self.parent1.clone(), self.parent2.clone());
+        for (collection, token_id) in [(parent1, token1), (parent2, token2)].iter() {
+            let msg = NftExecuteMsg::Approve {
+                spender: self.breeding.to_string(),
+                token_id: token_id.to_string(),
+                expires: None,
+            };
+            self.execute_nft(BREEDER, collection, &msg);
+        }
+    }
+
+    fn breed_msg(&self, token1: &str, token2: &str) -> HandleMsg {
+        HandleMsg::Breed {
+            parent1: ParentNft {
+                collection: self.parent1.to_string(),
+                token_id: token1.to_string(),
+            },
+            parent2: ParentNft {
+                collection: self.parent2.to_string(),
+                token_id: token2.to_string(),
+            },
+            proof: None,
+            auto_claim_tip: None,
+        }
+    }
+
+    fn breed(&mut self, token1: &str, token2: &str) -> Result<(), String> {
+        self.approve_parents(token1, token2);
+        let msg = self.breed_msg(token1, token2);
+        self.execute(BREEDER, msg, &coins(BREED_PRICE, DENOM))
+    }
+
+    fn advance_time(&mut self, seconds: u64) {
+        self.app.update_block(|block| block.time = block.time.plus_seconds(seconds));
+    }
+
+    fn advance_height(&mut self) -> u64 {
+        self.app.update_block(|block| block.height += 1);
+        self.app.block_info().height
+    }
+
+    fn balance(&self, address: &str) -> u128 {
+        self.app
+            .wrap()
+            .query_balance(address, DENOM)
+            .unwrap()
+            .amount
+            .u128()
+    }
+
+    fn owner_of(&self, collection: &Addr, token_id: &str) -> String {
+        let res: OwnerOfResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(
+                collection,
+                &Cw721QueryMsg::OwnerOf {
+                    token_id: token_id.to_string(),
+                    include_expired: None,
+                },
+            )
+            .unwrap();
+        res.owner
+    }
+}
+
+#[test]
+fn full_breeding_lifecycle() {
+    let mut suite = setup();
+    let breeding = suite.breeding.to_string();
+
+    suite.breed("cat1", "dog1").unwrap();
+
+    // The fee and both parents are escrowed by the contract
+    assert_eq!(suite.balance(BREEDER), 9 * BREED_PRICE);
+    assert_eq!(suite.balance(&breeding), BREED_PRICE);
+    assert_eq!(suite.owner_of(&suite.parent1, "cat1"), breeding);
+    assert_eq!(suite.owner_of(&suite.parent2, "dog1"), breeding);
+
+    // Escrowed fees cannot be withdrawn before the breed ends
+    let err = suite
+        .execute(OWNER, HandleMsg::WithdrawFund { denom: None, amount: None, recipient: None }, &[])
+        .unwrap_err();
+    assert_eq!(err, ContractError::NothingToWithdraw {}.to_string());
+
+    let err = suite.execute(BREEDER, HandleMsg::Withdraw { breed_id: 1 }, &[]).unwrap_err();
+    assert_eq!(err, ContractError::BreedNotFinished { breed_id: 1 }.to_string());
+
+    suite.advance_time(BREED_DURATION);
+    suite.execute(BREEDER, HandleMsg::Withdraw { breed_id: 1 }, &[]).unwrap();
+
+    // Parents go back to the breeder together with the child
+    assert_eq!(suite.owner_of(&suite.parent1, "cat1"), BREEDER);
+    assert_eq!(suite.owner_of(&suite.parent2, "dog1"), BREEDER);
+    assert_eq!(suite.owner_of(&suite.child, "1"), BREEDER);
+
+    let finished: u32 = suite
+        .app
+        .wrap()
+        .query_wasm_smart(&suite.breeding, &QueryMsg::BreedFinishedCount {})
+        .unwrap();
+    assert_eq!(finished, 1);
+
+    let treasury: TreasuryResponse = suite
+        .app
+        .wrap()
+        .query_wasm_smart(&suite.breeding, &QueryMsg::Treasury {})
+        .unwrap();
+    assert_eq!(treasury.entries[0].earned, Uint128::from(BREED_PRICE));
+    assert_eq!(treasury.entries[0].escrowed, Uint128::zero());
+
+    // The default payout table sends everything to the owner
+    suite
+        .execute(OWNER, HandleMsg::WithdrawFund { denom: None, amount: None, recipient: None }, &[])
+        .unwrap();
+    assert_eq!(suite.balance(OWNER), BREED_PRICE);
+    assert_eq!(suite.balance(&breeding), 0);
+
+    let err = suite.execute(BREEDER, HandleMsg::Withdraw { breed_id: 1 }, &[]).unwrap_err();
+    assert_eq!(err, ContractError::AlreadyWithdrawn { breed_id: 1 }.to_string());
+}
+
+#[test]
+fn cancel_breed_refunds_fee_and_parents() {
+    let mut suite = setup();
+    suite.breed("cat1", "dog1").unwrap();
+
+    let err = suite.execute(STRANGER, HandleMsg::CancelBreed { breed_id: 1 }, &[]).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {}.to_string());
+
+    suite.execute(BREEDER, HandleMsg::CancelBreed { breed_id: 1 }, &[]).unwrap();
+    assert_eq!(suite.balance(BREEDER), 10 * BREED_PRICE);
+    assert_eq!(suite.owner_of(&suite.parent1, "cat1"), BREEDER);
+    assert_eq!(suite.owner_of(&suite.parent2, "dog1"), BREEDER);
+
+    let err = suite.execute(BREEDER, HandleMsg::Withdraw { breed_id: 1 }, &[]).unwrap_err();
+    assert_eq!(err, ContractError::not_found("Breed").to_string());
+
+    // A finished breed can only be withdrawn
+    suite.breed("cat2", "dog2").unwrap();
+    suite.advance_time(BREED_DURATION);
+    let err = suite.execute(BREEDER, HandleMsg::CancelBreed { breed_id: 2 }, &[]).unwrap_err();
+    assert_eq!(err, ContractError::BreedFinished { breed_id: 2 }.to_string());
+}
+
+#[test]
+fn breed_errors() {
+    let mut suite = setup();
+    suite.approve_parents("cat1", "dog1");
+
+    let msg = suite.breed_msg("cat1", "dog1");
+    let err = suite.execute(BREEDER, msg.clone(), &coins(BREED_PRICE - 1, DENOM)).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::WrongFunds {
+            expected: Uint128::from(BREED_PRICE),
+            received: Uint128::from(BREED_PRICE - 1),
+        }
+        .to_string()
+    );
+
+    let balance = vec![coin(10 * BREED_PRICE, DENOM), coin(1, "uatom")];
+    suite.app.set_bank_balance(&Addr::unchecked(BREEDER), balance).unwrap();
+    let funds = vec![coin(BREED_PRICE, DENOM), coin(1, "uatom")];
+    let err = suite.execute(BREEDER, msg.clone(), &funds).unwrap_err();
+    assert_eq!(err, ContractError::UnexpectedFunds { denom: "uatom".to_string() }.to_string());
+
+    let err = suite.execute(STRANGER, msg, &coins(BREED_PRICE, DENOM)).unwrap_err();
+    assert_eq!(err, ContractError::NotParentOwner { token_id: "cat1".to_string() }.to_string());
+
+    let msg = HandleMsg::Breed {
+        parent1: ParentNft {
+            collection: suite.parent1.to_string(),
+            token_id: "cat1".to_string(),
+        },
+        parent2: ParentNft {
+            collection: suite.parent1.to_string(),
+            token_id: "cat1".to_string(),
+        },
+        proof: None,
+        auto_claim_tip: None,
+    };
+    let err = suite.execute(BREEDER, msg, &coins(BREED_PRICE, DENOM)).unwrap_err();
+    assert_eq!(err, ContractError::SelfBreeding {}.to_string());
+
+    let msg = HandleMsg::Breed {
+        parent1: ParentNft {
+            collection: suite.parent1.to_string(),
+            token_id: "cat1".to_string(),
+        },
+        parent2: ParentNft {
+            collection: suite.parent1.to_string(),
+            token_id: "cat2".to_string(),
+        },
+        proof: None,
+        auto_claim_tip: None,
+    };
+    let err = suite.execute(BREEDER, msg, &coins(BREED_PRICE, DENOM)).unwrap_err();
+    assert_eq!(err, ContractError::NotPaired {}.to_string());
+
+    // Nothing moved on failure
+    assert_eq!(suite.balance(BREEDER), 10 * BREED_PRICE);
+    assert_eq!(suite.owner_of(&suite.parent1, "cat1"), BREEDER);
+}
+
+#[test]
+fn breed_count_limit() {
+    let mut suite = setup();
+    suite
+        .execute(
+            OWNER,
+            HandleMsg::UpdateConfig {
+                breed_count_limit: Some(1),
+                breed_duration: None,
+                breed_price_amount: None,
+                breed_price_denom: None,
+                breed_price_cw20: None,
+                pricing: None,
+                max_batch_size: None,
+                child_token_id_scheme: None,
+                child_base_uri: None,
+                child_uri_template: None,
+                royalty: None,
+                owner: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    suite.breed("cat1", "dog1").unwrap();
+    let err = suite.breed("cat2", "dog2").unwrap_err();
+    assert_eq!(err, ContractError::LimitReached { limit: 1 }.to_string());
+
+    // A cancelled breed gives its slot back
+    suite.execute(BREEDER, HandleMsg::CancelBreed { breed_id: 1 }, &[]).unwrap();
+    suite.breed("cat2", "dog2").unwrap();
+}
+
+#[test]
+fn exponential_pricing() {
+    let mut suite = setup();
+    suite
+        .execute(
+            OWNER,
+            HandleMsg::UpdateConfig {
+                breed_count_limit: None,
+                breed_duration: None,
+                breed_price_amount: None,
+                breed_price_denom: None,
+                breed_price_cw20: None,
+                pricing: Some(PricingMode::Exponential { growth_bps: 5_000 }),
+                max_batch_size: None,
+                child_token_id_scheme: None,
+                child_base_uri: None,
+                child_uri_template: None,
+                royalty: None,
+                owner: None,
+            },
+            &[],
+        )
+        .unwrap();
+    // Pairings keep their own base price
+    suite
+        .execute(
+            OWNER,
+            HandleMsg::SetPairing {
+                collection1: suite.parent1.to_string(),
+                collection2: suite.parent2.to_string(),
+                breed_price_amount: Some(Uint128::from(1u128)),
+                breed_duration: None,
+                child_contract_addr: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    // 1 * 1.5 ^ 2 = 2.25, rounding every step would keep the price at 1
+    for (breed_id, (token1, token2)) in [("cat1", "dog1"), ("cat2", "dog2")].iter().enumerate() {
+        suite.approve_parents(token1, token2);
+        let msg = suite.breed_msg(token1, token2);
+        suite.execute(BREEDER, msg, &coins(1, DENOM)).unwrap();
+        suite.advance_time(BREED_DURATION);
+        suite
+            .execute(BREEDER, HandleMsg::Withdraw { breed_id: breed_id as u64 + 1 }, &[])
+            .unwrap();
+    }
+    let price: CurrentPriceResponse = suite
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &suite.breeding,
+            &QueryMsg::CurrentPrice {
+                collection1: suite.parent1.to_string(),
+                collection2: suite.parent2.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(price.minted, 2);
+    assert_eq!(price.amount, Uint128::from(2u128));
+}
+
+#[test]
+fn owner_only_handlers() {
+    let mut suite = setup();
+    let unauthorized = ContractError::Unauthorized {}.to_string();
+
+    let msgs = vec![
+        HandleMsg::UpdateConfig {
+            breed_count_limit: Some(1),
+            breed_duration: None,
+            breed_price_amount: None,
+            breed_price_denom: None,
+            breed_price_cw20: None,
+            pricing: None,
+            max_batch_size: None,
+            child_token_id_scheme: None,
+            child_base_uri: None,
+            child_uri_template: None,
+            royalty: None,
+            owner: Some(STRANGER.to_string()),
+        },
+        HandleMsg::WithdrawFund {
+            denom: None,
+            amount: None,
+            recipient: None,
+        },
+        HandleMsg::UpdatePayoutTable {
+            shares: vec![PayoutShareMsg {
+                recipient: STRANGER.to_string(),
+                share_bps: 10_000,
+            }],
+            dust_recipient: STRANGER.to_string(),
+        },
+        HandleMsg::SetAllowlist {
+            merkle_root: leaf_hash(STRANGER),
+            discount_bps: 5_000,
+            quota: 1,
+        },
+        HandleMsg::CloseAllowlist {},
+        HandleMsg::AddParentCollection {
+            addr: STRANGER.to_string(),
+        },
+        HandleMsg::RemoveParentCollection {
+            addr: suite.parent1.to_string(),
+        },
+        HandleMsg::SetPairing {
+            collection1: suite.parent1.to_string(),
+            collection2: suite.parent1.to_string(),
+            breed_price_amount: None,
+            breed_duration: None,
+            child_contract_addr: None,
+        },
+        HandleMsg::RemovePairing {
+            collection1: suite.parent1.to_string(),
+            collection2: suite.parent2.to_string(),
+        },
+        HandleMsg::InstantiateChildCollection {
+            collection: ChildCollectionMsg {
+                code_id: 2,
+                name: "Other".to_string(),
+                symbol: "OTHER".to_string(),
+                label: "other".to_string(),
+            },
+            pairing: None,
+        },
+        HandleMsg::InstantiateReceiptCollection {
+            collection: ChildCollectionMsg {
+                code_id: 2,
+                name: "Receipts".to_string(),
+                symbol: "RCPT".to_string(),
+                label: "receipts".to_string(),
+            },
+        },
+        HandleMsg::AddHook {
+            addr: STRANGER.to_string(),
+            events: vec![HookEvent::BreedStarted],
+        },
+        HandleMsg::RemoveHook {
+            addr: STRANGER.to_string(),
+        },
+    ];
+    for msg in msgs {
+        let err = suite.execute(STRANGER, msg, &[]).unwrap_err();
+        assert_eq!(err, unauthorized);
+    }
+}
+
+#[test]
+fn config_validation_errors() {
+    let mut suite = setup();
+
+    let err = suite
+        .execute(
+            OWNER,
+            HandleMsg::UpdateConfig {
+                breed_count_limit: None,
+                breed_duration: None,
+                breed_price_amount: None,
+                breed_price_denom: None,
+                breed_price_cw20: None,
+                pricing: Some(PricingMode::Stepped { tiers: vec![] }),
+                max_batch_size: None,
+                child_token_id_scheme: None,
+                child_base_uri: None,
+                child_uri_template: None,
+                royalty: None,
+                owner: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPricing {
+            reason: "stepped pricing needs at least one tier".to_string(),
+        }
+        .to_string()
+    );
+
+    let err = suite
+        .execute(
+            OWNER,
+            HandleMsg::UpdatePayoutTable {
+                shares: vec![PayoutShareMsg {
+                    recipient: OWNER.to_string(),
+                    share_bps: 5_000,
+                }],
+                dust_recipient: OWNER.to_string(),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPayoutTable {
+            reason: "shares must sum to 10000 basis points".to_string(),
+        }
+        .to_string()
+    );
+
+    let err = suite
+        .execute(
+            OWNER,
+            HandleMsg::SetPairing {
+                collection1: suite.parent1.to_string(),
+                collection2: STRANGER.to_string(),
+                breed_price_amount: None,
+                breed_duration: None,
+                child_contract_addr: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::UnregisteredCollection {}.to_string());
+
+    let err = suite
+        .execute(OWNER, HandleMsg::RemoveParentCollection { addr: suite.parent1.to_string() }, &[])
+        .unwrap_err();
+    assert_eq!(err, ContractError::CollectionInUse {}.to_string());
+}
+
+#[test]
+fn withdraw_fund_errors() {
+    let mut suite = setup();
+    suite.breed("cat1", "dog1").unwrap();
+    suite.advance_time(BREED_DURATION);
+    suite.execute(BREEDER, HandleMsg::Withdraw { breed_id: 1 }, &[]).unwrap();
+
+    let err = suite
+        .execute(
+            OWNER,
+            HandleMsg::WithdrawFund {
+                denom: None,
+                amount: Some(Uint128::from(1u128)),
+                recipient: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::DenomRequired {}.to_string());
+
+    let err = suite
+        .execute(
+            OWNER,
+            HandleMsg::WithdrawFund {
+                denom: Some(FeeDenomMsg::Native(DENOM.to_string())),
+                amount: Some(Uint128::from(BREED_PRICE + 1)),
+                recipient: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InsufficientFunds {
+            requested: Uint128::from(BREED_PRICE + 1),
+            available: Uint128::from(BREED_PRICE),
+        }
+        .to_string()
+    );
+
+    // A partial withdrawal to another recipient leaves the rest available
+    suite
+        .execute(
+            OWNER,
+            HandleMsg::WithdrawFund {
+                denom: Some(FeeDenomMsg::Native(DENOM.to_string())),
+                amount: Some(Uint128::from(400u128)),
+                recipient: Some(STRANGER.to_string()),
+            },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(suite.balance(STRANGER), 10 * BREED_PRICE + 400);
+    assert_eq!(suite.balance(suite.breeding.as_str()), BREED_PRICE - 400);
+}
+
+#[test]
+fn allowlist_discount_and_errors() {
+    let mut suite = setup();
+    suite.approve_parents("cat1", "dog1");
+
+    let mut msg = suite.breed_msg("cat1", "dog1");
+    if let HandleMsg::Breed { proof, .. } = &mut msg {
+        *proof = Some(vec![]);
+    }
+    let err = suite.execute(BREEDER, msg.clone(), &coins(BREED_PRICE, DENOM)).unwrap_err();
+    assert_eq!(err, ContractError::NoAllowlist {}.to_string());
+
+    let err = suite
+        .execute(
+            OWNER,
+            HandleMsg::SetAllowlist {
+                merkle_root: "not hex".to_string(),
+                discount_bps: 5_000,
+                quota: 1,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::InvalidHash {}.to_string());
+
+    let err = suite
+        .execute(
+            OWNER,
+            HandleMsg::SetAllowlist {
+                merkle_root: leaf_hash(BREEDER),
+                discount_bps: 10_001,
+                quota: 1,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::InvalidDiscount {}.to_string());
+
+    // A single leaf tree, the root is the breeder's leaf and the proof is empty
+    suite
+        .execute(
+            OWNER,
+            HandleMsg::SetAllowlist {
+                merkle_root: leaf_hash(BREEDER),
+                discount_bps: 5_000,
+                quota: 1,
+            },
+            &[],
+        )
+        .unwrap();
+
+    let mut invalid = msg.clone();
+    if let HandleMsg::Breed { proof, .. } = &mut invalid {
+        *proof = Some(vec![leaf_hash(STRANGER)]);
+    }
+    let err = suite.execute(BREEDER, invalid, &coins(BREED_PRICE / 2, DENOM)).unwrap_err();
+    assert_eq!(err, ContractError::InvalidProof {}.to_string());
+
+    suite.execute(BREEDER, msg.clone(), &coins(BREED_PRICE / 2, DENOM)).unwrap();
+    assert_eq!(suite.balance(BREEDER), 10 * BREED_PRICE - BREED_PRICE / 2);
+
+    suite.approve_parents("cat2", "dog2");
+    let mut msg = suite.breed_msg("cat2", "dog2");
+    if let HandleMsg::Breed { proof, .. } = &mut msg {
+        *proof = Some(vec![]);
+    }
+    let err = suite.execute(BREEDER, msg.clone(), &coins(BREED_PRICE / 2, DENOM)).unwrap_err();
+    assert_eq!(err, ContractError::QuotaExceeded {}.to_string());
+
+    // Cancelling the discounted breed gives its quota back
+    suite.execute(BREEDER, HandleMsg::CancelBreed { breed_id: 1 }, &[]).unwrap();
+    suite.execute(BREEDER, msg, &coins(BREED_PRICE / 2, DENOM)).unwrap();
+}
+
+#[test]
+fn pairing_falls_back_to_default_child_collection() {
+    let mut suite = setup();
+    let cw721_code_id = suite.app.store_code(contract_cw721());
+    let parent3 = parent_collection(&mut suite.app, cw721_code_id, "birds", &["bird1"]);
+
+    suite
+        .execute(OWNER, HandleMsg::AddParentCollection { addr: parent3.to_string() }, &[])
+        .unwrap();
+    suite
+        .execute(
+            OWNER,
+            HandleMsg::SetPairing {
+                collection1: suite.parent1.to_string(),
+                collection2: parent3.to_string(),
+                breed_price_amount: Some(Uint128::zero()),
+                breed_duration: None,
+                child_contract_addr: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    let msg = NftExecuteMsg::Approve {
+        spender: suite.breeding.to_string(),
+        token_id: "bird1".to_string(),
+        expires: None,
+    };
+    suite.execute_nft(BREEDER, &parent3, &msg);
+    let msg = NftExecuteMsg::Approve {
+        spender: suite.breeding.to_string(),
+        token_id: "cat1".to_string(),
+        expires: None,
+    };
+    let parent1 = suite.parent1.clone();
+    suite.execute_nft(BREEDER, &parent1, &msg);
+
+    let msg = HandleMsg::Breed {
+        parent1: ParentNft {
+            collection: suite.parent1.to_string(),
+            token_id: "cat1".to_string(),
+        },
+        parent2: ParentNft {
+            collection: parent3.to_string(),
+            token_id: "bird1".to_string(),
+        },
+        proof: None,
+        auto_claim_tip: None,
+    };
+    suite.execute(BREEDER, msg, &[]).unwrap();
+    suite.advance_time(BREED_DURATION);
+    suite.execute(BREEDER, HandleMsg::Withdraw { breed_id: 1 }, &[]).unwrap();
+    assert_eq!(suite.owner_of(&suite.child, "1"), BREEDER);
+}
+
+#[test]
+fn breed_batch() {
+    let mut suite = setup();
+    suite.approve_parents("cat1", "dog1");
+    suite.approve_parents("cat2", "dog2");
+
+    let (collection1, collection2) = (suite.parent1.to_string(), suite.parent2.to_string());
+    let batch = |pairs: &[(&str, &str)]| HandleMsg::BreedBatch {
+        collection1: collection1.clone(),
+        collection2: collection2.clone(),
+        pairs: pairs.iter().map(|(a, b)| (a.to_string(), b.to_string())).collect(),
+        auto_claim_tip: None,
+    };
+
+    let err = suite.execute(BREEDER, batch(&[]), &[]).unwrap_err();
+    assert_eq!(err, ContractError::EmptyBatch {}.to_string());
+
+    let msg = batch(&[("cat1", "dog1"), ("cat1", "dog2")]);
+    let err = suite.execute(BREEDER, msg, &coins(2 * BREED_PRICE, DENOM)).unwrap_err();
+    assert_eq!(err, ContractError::DuplicateParent { token_id: "cat1".to_string() }.to_string());
+
+    // One combined fee covers the whole batch
+    let msg = batch(&[("cat1", "dog1"), ("cat2", "dog2")]);
+    let err = suite.execute(BREEDER, msg.clone(), &coins(BREED_PRICE, DENOM)).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::WrongFunds {
+            expected: Uint128::from(2 * BREED_PRICE),
+            received: Uint128::from(BREED_PRICE),
+        }
+        .to_string()
+    );
+
+    // An invalid pair fails the whole batch
+    let parent1 = suite.parent1.clone();
+    let transfer = NftExecuteMsg::TransferNft {
+        recipient: STRANGER.to_string(),
+        token_id: "cat3".to_string(),
+    };
+    suite.execute_nft(BREEDER, &parent1, &transfer);
+    let invalid = batch(&[("cat1", "dog1"), ("cat3", "dog3")]);
+    let err = suite.execute(BREEDER, invalid, &coins(2 * BREED_PRICE, DENOM)).unwrap_err();
+    assert_eq!(err, ContractError::NotParentOwner { token_id: "cat3".to_string() }.to_string());
+    assert_eq!(suite.owner_of(&suite.parent1, "cat1"), BREEDER);
+    assert_eq!(suite.balance(BREEDER), 10 * BREED_PRICE);
+
+    let res = suite
+        .app
+        .execute_contract(
+            Addr::unchecked(BREEDER),
+            suite.breeding.clone(),
+            &msg,
+            &coins(2 * BREED_PRICE, DENOM),
+        )
+        .unwrap();
+    let data: BreedBatchResponse = from_binary(&res.data.unwrap()).unwrap();
+    assert_eq!(data.breed_ids, vec![1, 2]);
+    assert_eq!(suite.balance(BREEDER), 8 * BREED_PRICE);
+    assert_eq!(suite.owner_of(&suite.parent2, "dog2"), suite.breeding.to_string());
+
+    suite
+        .execute(
+            OWNER,
+            HandleMsg::UpdateConfig {
+                breed_count_limit: None,
+                breed_duration: None,
+                breed_price_amount: None,
+                breed_price_denom: None,
+                breed_price_cw20: None,
+                pricing: None,
+                max_batch_size: Some(1),
+                child_token_id_scheme: None,
+                child_base_uri: None,
+                child_uri_template: None,
+                royalty: None,
+                owner: None,
+            },
+            &[],
+        )
+        .unwrap();
+    let msg = batch(&[("cat3", "dog3"), ("cat1", "dog1")]);
+    let err = suite.execute(BREEDER, msg, &coins(2 * BREED_PRICE, DENOM)).unwrap_err();
+    assert_eq!(err, ContractError::BatchTooLarge { max: 1 }.to_string());
+}
+
+#[test]
+fn withdraw_all() {
+    let mut suite = setup();
+    suite.breed("cat1", "dog1").unwrap();
+    suite.breed("cat2", "dog2").unwrap();
+    suite.advance_time(BREED_DURATION);
+    suite.breed("cat3", "dog3").unwrap();
+
+    let withdraw_all = |suite: &mut Suite, start_after: Option<u64>, limit: u32| -> WithdrawAllResponse {
+        let res = suite
+            .app
+            .execute_contract(
+                Addr::unchecked(BREEDER),
+                suite.breeding.clone(),
+                &HandleMsg::WithdrawAll { start_after, limit },
+                &[],
+            )
+            .unwrap();
+        from_binary(&res.data.unwrap()).unwrap()
+    };
+
+    let res = withdraw_all(&mut suite, None, 1);
+    assert_eq!(res.breed_ids, vec![1]);
+    assert!(res.has_more);
+    assert_eq!(suite.owner_of(&suite.child, "1"), BREEDER);
+
+    // Unfinished breeds are skipped, the search resumes after the last withdrawn breed
+    let res = withdraw_all(&mut suite, Some(1), 10);
+    assert_eq!(res.breed_ids, vec![2]);
+    assert!(!res.has_more);
+    assert_eq!(suite.owner_of(&suite.parent1, "cat2"), BREEDER);
+    assert_eq!(suite.owner_of(&suite.parent1, "cat3"), suite.breeding.to_string());
+
+    let res = withdraw_all(&mut suite, None, 10);
+    assert!(res.breed_ids.is_empty());
+
+    // Other addresses have no breeds to walk
+    let res: WithdrawAllResponse = from_binary(
+        &suite
+            .app
+            .execute_contract(
+                Addr::unchecked(STRANGER),
+                suite.breeding.clone(),
+                &HandleMsg::WithdrawAll { start_after: None, limit: 10 },
+                &[],
+            )
+            .unwrap()
+            .data
+            .unwrap(),
+    )
+    .unwrap();
+    assert!(res.breed_ids.is_empty());
+
+    let err = suite.execute(BREEDER, HandleMsg::Withdraw { breed_id: 1 }, &[]).unwrap_err();
+    assert_eq!(err, ContractError::AlreadyWithdrawn { breed_id: 1 }.to_string());
+}
+
+#[test]
+fn claim_for() {
+    const TIP: u128 = 50;
+    let mut suite = setup();
+    let breeding = suite.breeding.to_string();
+
+    // Breed 1 opts into auto-claim, breed 2 does not
+    suite.approve_parents("cat1", "dog1");
+    let mut msg = suite.breed_msg("cat1", "dog1");
+    if let HandleMsg::Breed { auto_claim_tip, .. } = &mut msg {
+        *auto_claim_tip = Some(Uint128::from(TIP));
+    }
+    let err = suite.execute(BREEDER, msg.clone(), &coins(BREED_PRICE, DENOM)).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::WrongFunds {
+            expected: Uint128::from(BREED_PRICE + TIP),
+            received: Uint128::from(BREED_PRICE),
+        }
+        .to_string()
+    );
+    suite.execute(BREEDER, msg, &coins(BREED_PRICE + TIP, DENOM)).unwrap();
+    suite.breed("cat2", "dog2").unwrap();
+
+    let claimable = |suite: &Suite, start_after: Option<u64>| -> ClaimableBreedsResponse {
+        suite
+            .app
+            .wrap()
+            .query_wasm_smart(&suite.breeding, &QueryMsg::ClaimableBreeds { start_after, limit: None })
+            .unwrap()
+    };
+    assert!(claimable(&suite, None).breeds.is_empty());
+
+    let err = suite.execute(STRANGER, HandleMsg::ClaimFor { breed_id: 1 }, &[]).unwrap_err();
+    assert_eq!(err, ContractError::BreedNotFinished { breed_id: 1 }.to_string());
+
+    suite.advance_time(BREED_DURATION);
+    let breeds = claimable(&suite, None).breeds;
+    assert_eq!(breeds.len(), 1);
+    assert_eq!(breeds[0].breed_id, 1);
+    assert_eq!(breeds[0].owner, BREEDER.to_string());
+    assert_eq!(breeds[0].tip, Uint128::from(TIP));
+    assert_eq!(breeds[0].denom, FeeDenomMsg::Native(DENOM.to_string()));
+    assert!(claimable(&suite, Some(1)).breeds.is_empty());
+
+    let err = suite.execute(STRANGER, HandleMsg::ClaimFor { breed_id: 2 }, &[]).unwrap_err();
+    assert_eq!(err, ContractError::AutoClaimDisabled { breed_id: 2 }.to_string());
+
+    // The keeper gets the tip, the breeder gets the child and parents
+    let keeper_balance = suite.balance(STRANGER);
+    suite.execute(STRANGER, HandleMsg::ClaimFor { breed_id: 1 }, &[]).unwrap();
+    assert_eq!(suite.balance(STRANGER), keeper_balance + TIP);
+    assert_eq!(suite.owner_of(&suite.child, "1"), BREEDER);
+    assert_eq!(suite.owner_of(&suite.parent1, "cat1"), BREEDER);
+    assert_eq!(suite.owner_of(&suite.parent2, "dog1"), BREEDER);
+    assert_eq!(suite.balance(&breeding), 2 * BREED_PRICE);
+    assert!(claimable(&suite, None).breeds.is_empty());
+
+    let err = suite.execute(STRANGER, HandleMsg::ClaimFor { breed_id: 1 }, &[]).unwrap_err();
+    assert_eq!(err, ContractError::AlreadyWithdrawn { breed_id: 1 }.to_string());
+
+    let treasury: TreasuryResponse = suite
+        .app
+        .wrap()
+        .query_wasm_smart(&suite.breeding, &QueryMsg::Treasury {})
+        .unwrap();
+    assert_eq!(treasury.entries[0].tips, Uint128::zero());
+}
+
+#[test]
+fn breed_receipts() {
+    let mut suite = setup();
+    let receipts = suite.minted_collection("receipts");
+    suite.sudo(collections_msg(None, Some(&receipts))).unwrap();
+
+    // Keepers cannot send receipts, so receipt breeds do not opt into auto-claim
+    suite.approve_parents("cat1", "dog1");
+    let mut msg = suite.breed_msg("cat1", "dog1");
+    if let HandleMsg::Breed { auto_claim_tip, .. } = &mut msg {
+        *auto_claim_tip = Some(Uint128::from(10u128));
+    }
+    let err = suite.execute(BREEDER, msg, &coins(BREED_PRICE + 10, DENOM)).unwrap_err();
+    assert_eq!(err, ContractError::ReceiptAutoClaim {}.to_string());
+
+    suite.breed("cat1", "dog1").unwrap();
+    suite.breed("cat2", "dog2").unwrap();
+    assert_eq!(suite.owner_of(&receipts, "1"), BREEDER);
+
+    // Selling the receipt hands the incubating breed over
+    let msg = NftExecuteMsg::TransferNft {
+        recipient: STRANGER.to_string(),
+        token_id: "1".to_string(),
+    };
+    suite.execute_nft(BREEDER, &receipts, &msg);
+
+    // The breed is only settled by sending its receipt, along with what to do
+    let err = suite.execute(STRANGER, HandleMsg::CancelBreed { breed_id: 1 }, &[]).unwrap_err();
+    assert_eq!(err, ContractError::ReceiptRequired { breed_id: 1 }.to_string());
+    suite.advance_time(BREED_DURATION);
+    let err = suite.execute(STRANGER, HandleMsg::Withdraw { breed_id: 1 }, &[]).unwrap_err();
+    assert_eq!(err, ContractError::ReceiptRequired { breed_id: 1 }.to_string());
+    let err = suite.send_receipt(STRANGER, &receipts, 1, None).unwrap_err();
+    assert_eq!(err, ContractError::InvalidReceipt {}.to_string());
+
+    // Settling spends the receipt, the contract keeps it
+    let breeding = suite.breeding.to_string();
+    suite.send_receipt(STRANGER, &receipts, 1, Some(ReceiptMsg::Withdraw {})).unwrap();
+    assert_eq!(suite.owner_of(&suite.child, "1"), STRANGER);
+    assert_eq!(suite.owner_of(&suite.parent1, "cat1"), STRANGER);
+    assert_eq!(suite.owner_of(&suite.parent2, "dog1"), STRANGER);
+    assert_eq!(suite.owner_of(&receipts, "1"), breeding);
+
+    // WithdrawAll skips the breeds held through a receipt
+    let res = suite
+        .app
+        .execute_contract(
+            Addr::unchecked(BREEDER),
+            suite.breeding.clone(),
+            &HandleMsg::WithdrawAll { start_after: None, limit: 10 },
+            &[],
+        )
+        .unwrap();
+    let withdrawn: WithdrawAllResponse = from_binary(&res.data.unwrap()).unwrap();
+    assert!(withdrawn.breed_ids.is_empty());
+    suite.send_receipt(BREEDER, &receipts, 2, Some(ReceiptMsg::Withdraw {})).unwrap();
+    assert_eq!(suite.owner_of(&suite.child, "2"), BREEDER);
+    assert_eq!(suite.owner_of(&receipts, "2"), breeding);
+
+    // Cancelling through the receipt also spends it
+    suite.breed("cat3", "dog3").unwrap();
+    suite.send_receipt(BREEDER, &receipts, 3, Some(ReceiptMsg::Cancel {})).unwrap();
+    assert_eq!(suite.owner_of(&suite.parent1, "cat3"), BREEDER);
+    assert_eq!(suite.owner_of(&receipts, "3"), breeding);
+}
+
+#[test]
+fn transfer_and_offer_breed() {
+    let mut suite = setup();
+    suite.breed("cat1", "dog1").unwrap();
+    suite.breed("cat2", "dog2").unwrap();
+
+    let transfer = |breed_id: u64, recipient: &str| HandleMsg::TransferBreed {
+        breed_id,
+        recipient: recipient.to_string(),
+    };
+    let err = suite.execute(STRANGER, transfer(1, STRANGER), &[]).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {}.to_string());
+
+    // The recipient takes over the breed and can cancel it
+    suite.execute(BREEDER, transfer(1, STRANGER), &[]).unwrap();
+    let user_breedings = |suite: &Suite, user: &str| -> u32 {
+        suite
+            .app
+            .wrap()
+            .query_wasm_smart(&suite.breeding, &QueryMsg::QueryUserBreedingsLength { user: user.to_string() })
+            .unwrap()
+    };
+    assert_eq!(user_breedings(&suite, STRANGER), 1);
+    assert_eq!(user_breedings(&suite, BREEDER), 1);
+    let err = suite.execute(BREEDER, HandleMsg::CancelBreed { breed_id: 1 }, &[]).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {}.to_string());
+
+    // Cancelling drops the pending offer and refunds the fee to the breeder who paid it
+    suite
+        .execute(
+            STRANGER,
+            HandleMsg::OfferBreed {
+                breed_id: 1,
+                recipient: OWNER.to_string(),
+                price: None,
+            },
+            &[],
+        )
+        .unwrap();
+    let (breeder_balance, stranger_balance) = (suite.balance(BREEDER), suite.balance(STRANGER));
+    suite.execute(STRANGER, HandleMsg::CancelBreed { breed_id: 1 }, &[]).unwrap();
+    assert_eq!(suite.owner_of(&suite.parent1, "cat1"), STRANGER);
+    assert_eq!(suite.balance(BREEDER), breeder_balance + BREED_PRICE);
+    assert_eq!(suite.balance(STRANGER), stranger_balance);
+    assert_eq!(user_breedings(&suite, STRANGER), 0);
+    let err = suite.execute(OWNER, HandleMsg::AcceptBreed { breed_id: 1 }, &[]).unwrap_err();
+    assert_eq!(err, ContractError::not_found("Breed offer").to_string());
+
+    // A priced offer is paid to the owner when accepted
+    let price = coin(300, DENOM);
+    suite
+        .execute(
+            BREEDER,
+            HandleMsg::OfferBreed {
+                breed_id: 2,
+                recipient: STRANGER.to_string(),
+                price: Some(price.clone()),
+            },
+            &[],
+        )
+        .unwrap();
+    let err = suite.execute(OWNER, HandleMsg::AcceptBreed { breed_id: 2 }, &[]).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {}.to_string());
+    let err = suite
+        .execute(STRANGER, HandleMsg::AcceptBreed { breed_id: 2 }, &coins(100, DENOM))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::WrongFunds {
+            expected: price.amount,
+            received: Uint128::from(100u128),
+        }
+        .to_string()
+    );
+
+    let (breeder_balance, stranger_balance) = (suite.balance(BREEDER), suite.balance(STRANGER));
+    suite.execute(STRANGER, HandleMsg::AcceptBreed { breed_id: 2 }, &[price]).unwrap();
+    assert_eq!(suite.balance(BREEDER), breeder_balance + 300);
+    assert_eq!(suite.balance(STRANGER), stranger_balance - 300);
+
+    // Accepting clears the offer
+    let err = suite.execute(STRANGER, HandleMsg::AcceptBreed { breed_id: 2 }, &[]).unwrap_err();
+    assert_eq!(err, ContractError::not_found("Breed offer").to_string());
+
+    suite.advance_time(BREED_DURATION);
+    suite.execute(STRANGER, HandleMsg::Withdraw { breed_id: 2 }, &[]).unwrap();
+    assert_eq!(suite.owner_of(&suite.child, "2"), STRANGER);
+    // The accepted breed moved to the recipient and stays listed once withdrawn
+    assert_eq!(user_breedings(&suite, STRANGER), 1);
+    assert_eq!(user_breedings(&suite, BREEDER), 0);
+    let err = suite.execute(STRANGER, transfer(2, BREEDER), &[]).unwrap_err();
+    assert_eq!(err, ContractError::AlreadyWithdrawn { breed_id: 2 }.to_string());
+}
+
+#[test]
+fn sudo_governance() {
+    let mut suite = setup();
+    suite.breed("cat1", "dog1").unwrap();
+    suite
+        .execute(
+            BREEDER,
+            HandleMsg::TransferBreed {
+                breed_id: 1,
+                recipient: STRANGER.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+    suite
+        .execute(
+            STRANGER,
+            HandleMsg::OfferBreed {
+                breed_id: 1,
+                recipient: OWNER.to_string(),
+                price: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    // Rescued parents go to the breed holder, the fee back to the breeder, and the offer is dropped
+    let rescue = |breed_id: u64| SudoMsg::RescueNfts { breed_id, recipient: None };
+    suite.sudo(rescue(1)).unwrap();
+    assert_eq!(suite.owner_of(&suite.parent1, "cat1"), STRANGER);
+    assert_eq!(suite.owner_of(&suite.parent2, "dog1"), STRANGER);
+    assert_eq!(suite.balance(BREEDER), 10 * BREED_PRICE);
+    let err = suite.execute(OWNER, HandleMsg::AcceptBreed { breed_id: 1 }, &[]).unwrap_err();
+    assert_eq!(err, ContractError::not_found("Breed offer").to_string());
+    let err = suite.execute(STRANGER, HandleMsg::Withdraw { breed_id: 1 }, &[]).unwrap_err();
+    assert_eq!(err, ContractError::not_found("Breed").to_string());
+    let err = suite.sudo(rescue(1)).unwrap_err();
+    assert_eq!(err, ContractError::not_found("Breed").to_string());
+
+    suite.sudo(SudoMsg::ForcePause { paused: true }).unwrap();
+    let err = suite.breed("cat2", "dog2").unwrap_err();
+    assert_eq!(err, ContractError::Paused {}.to_string());
+    suite.sudo(SudoMsg::ForcePause { paused: false }).unwrap();
+    suite.breed("cat2", "dog2").unwrap();
+
+    // The config update can also pause and repoint the receipt collection
+    let receipts = suite.minted_collection("receipts");
+    suite
+        .sudo(SudoMsg::UpdateConfig {
+            breed_count_limit: Some(5),
+            breed_duration: None,
+            breed_price_amount: None,
+            breed_price_denom: None,
+            breed_price_cw20: None,
+            pricing: None,
+            max_batch_size: None,
+            child_token_id_scheme: None,
+            child_base_uri: None,
+            child_uri_template: None,
+            royalty: None,
+            child_contract_addr: None,
+            receipt_contract_addr: Some(receipts.to_string()),
+            paused: Some(true),
+        })
+        .unwrap();
+    let config: ConfigResponse = suite
+        .app
+        .wrap()
+        .query_wasm_smart(&suite.breeding, &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(config.breed_count_limit, 5);
+    assert_eq!(config.receipt_contract_addr, Some(receipts.to_string()));
+    assert!(config.paused);
+    let err = suite.breed("cat3", "dog3").unwrap_err();
+    assert_eq!(err, ContractError::Paused {}.to_string());
+    suite.sudo(SudoMsg::ForcePause { paused: false }).unwrap();
+    suite.breed("cat3", "dog3").unwrap();
+    assert_eq!(suite.owner_of(&receipts, "3"), BREEDER);
+
+    // A rescued receipt breed keeps its parents until the receipt is sent
+    let breeding = suite.breeding.to_string();
+    suite.sudo(rescue(3)).unwrap();
+    assert_eq!(suite.owner_of(&suite.parent1, "cat3"), breeding);
+    suite.send_receipt(BREEDER, &receipts, 3, Some(ReceiptMsg::Cancel {})).unwrap();
+    assert_eq!(suite.owner_of(&suite.parent1, "cat3"), BREEDER);
+    assert_eq!(suite.owner_of(&suite.parent2, "dog3"), BREEDER);
+    assert_eq!(suite.owner_of(&receipts, "3"), breeding);
+
+    suite.sudo(SudoMsg::ReplaceOwner { owner: STRANGER.to_string() }).unwrap();
+    let err = suite.execute(OWNER, HandleMsg::Mint { extension: None }, &[]).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {}.to_string());
+    suite.execute(STRANGER, HandleMsg::Mint { extension: None }, &[]).unwrap();
+}
+
+#[test]
+fn stats_at_height() {
+    let mut suite = setup();
+    let stats = |suite: &Suite, address: Option<&str>, height: u64| -> BreedStatsResponse {
+        let msg = match address {
+            Some(address) => QueryMsg::BreedStatsAtHeight {
+                address: address.to_string(),
+                height,
+            },
+            None => QueryMsg::TotalStatsAtHeight { height },
+        };
+        suite.app.wrap().query_wasm_smart(&suite.breeding, &msg).unwrap()
+    };
+
+    let start = suite.advance_height();
+    suite.breed("cat1", "dog1").unwrap();
+    suite.breed("cat2", "dog2").unwrap();
+    let bred = suite.advance_height();
+    suite.advance_time(BREED_DURATION);
+    suite.execute(BREEDER, HandleMsg::Withdraw { breed_id: 1 }, &[]).unwrap();
+    let withdrawn = suite.advance_height();
+
+    // Changes made in a block only show from the next height on
+    let at_start = stats(&suite, None, start);
+    assert_eq!((at_start.breeds, at_start.children), (0, 0));
+    let at_bred = stats(&suite, None, bred);
+    assert_eq!((at_bred.breeds, at_bred.children), (2, 0));
+    let before_withdrawn = stats(&suite, Some(BREEDER), withdrawn - 1);
+    assert_eq!((before_withdrawn.breeds, before_withdrawn.children), (2, 0));
+    let at_withdrawn = stats(&suite, Some(BREEDER), withdrawn);
+    assert_eq!((at_withdrawn.breeds, at_withdrawn.children), (2, 1));
+    assert_eq!(at_withdrawn.height, withdrawn);
+
+    let stranger = stats(&suite, Some(STRANGER), withdrawn);
+    assert_eq!((stranger.breeds, stranger.children), (0, 0));
+
+    // A cancelled breed drops out of the breeder's count, even after changing hands
+    suite.breed("cat3", "dog3").unwrap();
+    suite
+        .execute(
+            BREEDER,
+            HandleMsg::TransferBreed {
+                breed_id: 3,
+                recipient: STRANGER.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+    let rebred = suite.advance_height();
+    suite.execute(STRANGER, HandleMsg::CancelBreed { breed_id: 3 }, &[]).unwrap();
+    let cancelled = suite.advance_height();
+    let at_rebred = stats(&suite, Some(BREEDER), rebred);
+    assert_eq!((at_rebred.breeds, at_rebred.children), (3, 1));
+    let at_cancelled = stats(&suite, Some(BREEDER), cancelled);
+    assert_eq!((at_cancelled.breeds, at_cancelled.children), (2, 1));
+    let stranger = stats(&suite, Some(STRANGER), cancelled);
+    assert_eq!((stranger.breeds, stranger.children), (0, 0));
+    let totals = stats(&suite, None, cancelled);
+    assert_eq!((totals.breeds, totals.children), (2, 1));
+}
+
+#[test]
+fn leaderboards() {
+    let mut suite = setup();
+    let leaderboard = |suite: &Suite, kind: LeaderboardKindMsg| -> LeaderboardResponse {
+        suite
+            .app
+            .wrap()
+            .query_wasm_smart(
+                &suite.breeding,
+                &QueryMsg::Leaderboard {
+                    kind,
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap()
+    };
+    let spenders = || LeaderboardKindMsg::Spenders {
+        denom: FeeDenomMsg::Native(DENOM.to_string()),
+    };
+
+    suite.breed("cat1", "dog1").unwrap();
+    suite.breed("cat2", "dog2").unwrap();
+    suite.advance_time(BREED_DURATION);
+    suite.execute(BREEDER, HandleMsg::Withdraw { breed_id: 1 }, &[]).unwrap();
+    suite.execute(BREEDER, HandleMsg::Withdraw { breed_id: 2 }, &[]).unwrap();
+
+    // cat1 and dog1 breed again, STRANGER pays for it and then cancels
+    let (parent1, parent2) = (suite.parent1.clone(), suite.parent2.clone());
+    for (collection, token_id) in [(&parent1, "cat1"), (&parent2, "dog1")].iter() {
+        let msg = NftExecuteMsg::TransferNft {
+            recipient: STRANGER.to_string(),
+            token_id: token_id.to_string(),
+        };
+        suite.execute_nft(BREEDER, collection, &msg);
+        let msg = NftExecuteMsg::Approve {
+            spender: suite.breeding.to_string(),
+            token_id: token_id.to_string(),
+            expires: None,
+        };
+        suite.execute_nft(STRANGER, collection, &msg);
+    }
+    let msg = suite.breed_msg("cat1", "dog1");
+    suite.execute(STRANGER, msg, &coins(BREED_PRICE, DENOM)).unwrap();
+
+    let board = leaderboard(&suite, spenders());
+    assert_eq!(board.entries.len(), 2);
+    assert_eq!(board.entries[0].address, BREEDER.to_string());
+    assert_eq!(board.entries[0].score, Uint128::from(2 * BREED_PRICE));
+    assert_eq!(board.entries[1].rank, 2);
+    assert_eq!(board.entries[1].score, Uint128::from(BREED_PRICE));
+
+    suite.execute(STRANGER, HandleMsg::CancelBreed { breed_id: 3 }, &[]).unwrap();
+    assert_eq!(leaderboard(&suite, spenders()).entries.len(), 1);
+
+    let board = leaderboard(&suite, LeaderboardKindMsg::Breeders {});
+    assert_eq!(board.entries.len(), 1);
+    assert_eq!(board.entries[0].score, Uint128::from(2u128));
+
+    let board = leaderboard(&suite, LeaderboardKindMsg::Parents {});
+    assert_eq!(board.entries.len(), 4);
+    assert!(board.entries.iter().all(|entry| entry.score == Uint128::from(1u128)));
+    assert!(board
+        .entries
+        .iter()
+        .any(|entry| entry.address == suite.parent1.as_str() && entry.token_id == Some("cat1".to_string())));
+
+    // Pages resume after the score and key of the last entry, ranks carry on across pages
+    let page = |suite: &Suite, start_after: Option<(Uint128, String)>| -> StdResult<LeaderboardResponse> {
+        suite.app.wrap().query_wasm_smart(
+            &suite.breeding,
+            &QueryMsg::Leaderboard {
+                kind: LeaderboardKindMsg::Parents {},
+                start_after,
+                limit: Some(2),
+            },
+        )
+    };
+    let first = page(&suite, None).unwrap();
+    let last = &first.entries[1];
+    let key = format!("{}/{}", last.address, last.token_id.clone().unwrap());
+    let second = page(&suite, Some((last.score, key))).unwrap();
+    assert_eq!(second.entries.iter().map(|entry| entry.rank).collect::<Vec<_>>(), vec![3, 4]);
+    assert_eq!([first.entries, second.entries].concat(), board.entries);
+    assert!(page(&suite, Some((Uint128::from(1u128), suite.parent1.to_string()))).is_err());
+}
+
+fn naming_msg(
+    scheme: Option<ChildTokenIdScheme>,
+    base_uri: Option<&str>,
+    template: Option<&str>,
+) -> HandleMsg {
+    HandleMsg::UpdateConfig {
+        breed_count_limit: None,
+        breed_duration: None,
+        breed_price_amount: None,
+        breed_price_denom: None,
+        breed_price_cw20: None,
+        pricing: None,
+        max_batch_size: None,
+        child_token_id_scheme: scheme,
+        child_base_uri: base_uri.map(String::from),
+        child_uri_template: template.map(String::from),
+        royalty: None,
+        owner: None,
+    }
+}
+
+fn preview_child_uri(suite: &Suite, id: u64, template: Option<&str>) -> StdResult<PreviewChildUriResponse> {
+    suite.app.wrap().query_wasm_smart(
+        &suite.breeding,
+        &QueryMsg::PreviewChildUri {
+            id,
+            child_base_uri: None,
+            child_uri_template: template.map(String::from),
+        },
+    )
+}
+
+#[test]
+fn child_naming() {
+    let mut suite = setup();
+
+    let invalid = |reason: &str| ContractError::InvalidChildNaming { reason: reason.to_string() }.to_string();
+    let err = suite
+        .execute(OWNER, naming_msg(None, None, Some("{base}/{id}.json")), &[])
+        .unwrap_err();
+    assert_eq!(err, invalid("uri template uses {base} without a base uri"));
+    let err = suite
+        .execute(OWNER, naming_msg(Some(ChildTokenIdScheme::Padded { width: 0 }), None, None), &[])
+        .unwrap_err();
+    assert_eq!(err, invalid("padded width must be between 1 and 20"));
+    let err = suite
+        .execute(
+            OWNER,
+            naming_msg(Some(ChildTokenIdScheme::Sequential { prefix: "kitten2".to_string() }), None, None),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err, invalid("sequential prefix must not end with a digit"));
+
+    suite
+        .execute(
+            OWNER,
+            naming_msg(
+                Some(ChildTokenIdScheme::Padded { width: 5 }),
+                Some("ipfs://children"),
+                Some("{base}/{id}.json"),
+            ),
+            &[],
+        )
+        .unwrap();
+    let preview = preview_child_uri(&suite, 1, None).unwrap();
+    assert_eq!(preview.token_id, "00001");
+    assert_eq!(preview.token_uri, Some("ipfs://children/00001.json".to_string()));
+    let preview = preview_child_uri(&suite, 1, Some("https://example.com/{id}")).unwrap();
+    assert_eq!(preview.token_uri, Some("https://example.com/00001".to_string()));
+    assert!(preview_child_uri(&suite, 1, Some("{base}/{name}.json")).is_err());
+
+    suite.breed("cat1", "dog1").unwrap();
+    suite.advance_time(BREED_DURATION);
+    suite.execute(BREEDER, HandleMsg::Withdraw { breed_id: 1 }, &[]).unwrap();
+    assert_eq!(suite.owner_of(&suite.child, "00001"), BREEDER);
+    let info = child_info(&suite, "00001").unwrap();
+    assert_eq!(info.token_uri, Some("ipfs://children/00001.json".to_string()));
+    assert_eq!(info.metadata, None);
+    assert!(child_info(&suite, "00009").is_err());
+
+    // Direct mints take the next breed id
+    let err = suite.execute(STRANGER, HandleMsg::Mint { extension: None }, &[]).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {}.to_string());
+    suite.execute(OWNER, HandleMsg::Mint { extension: None }, &[]).unwrap();
+    assert_eq!(suite.owner_of(&suite.child, "00002"), OWNER);
+    suite.breed("cat2", "dog2").unwrap();
+    let info: BreedInfoResponse = suite
+        .app
+        .wrap()
+        .query_wasm_smart(&suite.breeding, &QueryMsg::BreedInfo { breed_id: 3 })
+        .unwrap();
+    assert_eq!(info.nft_token_id1, "cat2");
+
+    // Hashed ids of an existing breed cover its parents, changing the scheme keeps minted ids
+    suite
+        .execute(OWNER, naming_msg(Some(ChildTokenIdScheme::Hash {}), Some(""), Some("")), &[])
+        .unwrap();
+    let preview = preview_child_uri(&suite, 1, None).unwrap();
+    assert_eq!(preview.token_id, leaf_hash("cat1:dog1:1"));
+    assert_eq!(preview.token_uri, None);
+    let info: BreedInfoResponse = suite
+        .app
+        .wrap()
+        .query_wasm_smart(&suite.breeding, &QueryMsg::BreedInfo { breed_id: 1 })
+        .unwrap();
+    assert_eq!(info.child_token_id, Some("00001".to_string()));
+}
+
+fn child_info(suite: &Suite, token_id: &str) -> StdResult<ChildInfoResponse> {
+    suite.app.wrap().query_wasm_smart(
+        &suite.breeding,
+        &QueryMsg::ChildInfo {
+            token_id: token_id.to_string(),
+        },
+    )
+}
+
+#[test]
+fn mint_metadata() {
+    let mut suite = setup();
+    let metadata = Metadata {
+        name: Some("Kitten".to_string()),
+        image: Some("ipfs://children/kitten.png".to_string()),
+        attributes: Some(vec![Trait {
+            display_type: None,
+            trait_type: "fur".to_string(),
+            value: "tabby".to_string(),
+        }]),
+        ..Metadata::default()
+    };
+
+    let invalid = |reason: &str| ContractError::InvalidMetadata { reason: reason.to_string() }.to_string();
+    let mint = |metadata: Metadata| HandleMsg::Mint { extension: Some(metadata) };
+    let err = suite
+        .execute(OWNER, mint(Metadata { name: None, ..metadata.clone() }), &[])
+        .unwrap_err();
+    assert_eq!(err, invalid("name is required"));
+    let err = suite
+        .execute(OWNER, mint(Metadata { image: Some("x".repeat(513)), ..metadata.clone() }), &[])
+        .unwrap_err();
+    assert_eq!(err, invalid("image is longer than 512 bytes"));
+    let err = suite
+        .execute(
+            OWNER,
+            mint(Metadata {
+                attributes: Some(vec![Trait {
+                    display_type: None,
+                    trait_type: "fur".to_string(),
+                    value: " ".to_string(),
+                }]),
+                ..metadata.clone()
+            }),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err, invalid("attributes need a trait_type and a value"));
+
+    let err = suite.execute(STRANGER, mint(metadata.clone()), &[]).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {}.to_string());
+    suite.execute(OWNER, mint(metadata.clone()), &[]).unwrap();
+    assert_eq!(suite.owner_of(&suite.child, "1"), OWNER);
+
+    // The child counts as minted for pricing, stats and the breeders leaderboard, but not as a breed
+    let height = suite.advance_height();
+    let stats: BreedStatsResponse = suite
+        .app
+        .wrap()
+        .query_wasm_smart(&suite.breeding, &QueryMsg::TotalStatsAtHeight { height })
+        .unwrap();
+    assert_eq!((stats.breeds, stats.children), (0, 1));
+    let price: CurrentPriceResponse = suite
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &suite.breeding,
+            &QueryMsg::CurrentPrice {
+                collection1: suite.parent1.to_string(),
+                collection2: suite.parent2.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(price.minted, 1);
+    let finished: u32 = suite
+        .app
+        .wrap()
+        .query_wasm_smart(&suite.breeding, &QueryMsg::BreedFinishedCount {})
+        .unwrap();
+    assert_eq!(finished, 0);
+    let board: LeaderboardResponse = suite
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &suite.breeding,
+            &QueryMsg::Leaderboard {
+                kind: LeaderboardKindMsg::Breeders {},
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(board.entries[0].address, OWNER);
+    assert_eq!(board.entries[0].score, Uint128::from(1u128));
+
+    // The attributes only live in the breeding contract
+    let info = child_info(&suite, "1").unwrap();
+    assert_eq!(info.token_uri, None);
+    assert_eq!(info.metadata, Some(metadata));
+}
+
+fn royalty_msg(percentage: u64, parent_share_bps: u16) -> HandleMsg {
+    HandleMsg::UpdateConfig {
+        breed_count_limit: None,
+        breed_duration: None,
+        breed_price_amount: None,
+        breed_price_denom: None,
+        breed_price_cw20: None,
+        pricing: None,
+        max_batch_size: None,
+        child_token_id_scheme: None,
+        child_base_uri: None,
+        child_uri_template: None,
+        royalty: Some(RoyaltyMsg {
+            payment_address: OWNER.to_string(),
+            percentage,
+            parent_share_bps,
+        }),
+        owner: None,
+    }
+}
+
+fn royalty_info(suite: &Suite, token_id: &str) -> StdResult<RoyaltiesInfoResponse> {
+    suite.app.wrap().query_wasm_smart(
+        &suite.breeding,
+        &QueryMsg::RoyaltyInfo {
+            token_id: token_id.to_string(),
+            sale_price: Uint128::from(1_000u128),
+        },
+    )
+}
+
+#[test]
+fn royalties() {
+    let mut suite = setup();
+
+    let err = suite.execute(OWNER, royalty_msg(101, 0), &[]).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidRoyalty {
+            reason: "percentage cannot exceed 100".to_string(),
+        }
+        .to_string()
+    );
+    suite.execute(OWNER, royalty_msg(5, 4_000), &[]).unwrap();
+    let check: CheckRoyaltiesResponse = suite
+        .app
+        .wrap()
+        .query_wasm_smart(&suite.breeding, &QueryMsg::CheckRoyalties {})
+        .unwrap();
+    assert!(check.royalty_payments);
+
+    // Marketplaces pay the payment address, royalties sent through PayRoyalty are split with the breeder
+    suite.breed("cat1", "dog1").unwrap();
+    suite.advance_time(BREED_DURATION);
+    suite.execute(BREEDER, HandleMsg::Withdraw { breed_id: 1 }, &[]).unwrap();
+    let info = royalty_info(&suite, "1").unwrap();
+    assert_eq!(info.address, OWNER);
+    assert_eq!(info.royalty_amount, Uint128::from(50u128));
+
+    let err = suite.execute(STRANGER, HandleMsg::PayRoyalty { token_id: "1".to_string() }, &[]).unwrap_err();
+    assert_eq!(err, ContractError::NoRoyaltyFunds {}.to_string());
+    suite
+        .execute(STRANGER, HandleMsg::PayRoyalty { token_id: "1".to_string() }, &coins(50, DENOM))
+        .unwrap();
+    assert_eq!(suite.balance(OWNER), 30);
+    assert_eq!(suite.balance(BREEDER), 9 * BREED_PRICE + 20);
+
+    // Direct mints have no parents, PayRoyalty forwards everything to the payment address
+    suite.execute(OWNER, HandleMsg::Mint { extension: None }, &[]).unwrap();
+    let info = royalty_info(&suite, "2").unwrap();
+    assert_eq!(info.address, OWNER);
+    assert_eq!(info.royalty_amount, Uint128::from(50u128));
+    suite
+        .execute(STRANGER, HandleMsg::PayRoyalty { token_id: "2".to_string() }, &coins(50, DENOM))
+        .unwrap();
+    assert_eq!(suite.balance(OWNER), 80);
+
+    // Children without a royalty owe nothing, as CW-2981 expects
+    let info = royalty_info(&suite, "3").unwrap();
+    assert_eq!(info.address, "");
+    assert_eq!(info.royalty_amount, Uint128::zero());
+    let err = suite
+        .execute(STRANGER, HandleMsg::PayRoyalty { token_id: "3".to_string() }, &coins(50, DENOM))
+        .unwrap_err();
+    assert_eq!(err, ContractError::not_found("Royalty").to_string());
+}
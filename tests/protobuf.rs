@@ -0,0 +1,285 @@
+// protobuf.rs
+//
+// Checks that the protobuf encoded query responses carry the same data as their JSON form.
+
+#![cfg(feature = "protobuf")]
+
+use std::fmt::Debug;
+
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{
+    coins, from_binary, from_slice, to_binary, ContractResult, Empty, OwnedDeps, Querier,
+    QuerierResult, QueryRequest, SystemResult, Uint128, WasmQuery,
+};
+use cw721::OwnerOfResponse;
+use prost::Message;
+use serde::de::DeserializeOwned;
+
+use my_contract::contract::{execute, instantiate, query};
+use my_contract::error::ContractError;
+use my_contract::msg::{
+    AllowlistStatusResponse, BreedInfoResponse, BreedStatsResponse, ClaimableBreedsResponse,
+    ConfigResponse, CurrentPriceResponse, HandleMsg, HooksResponse, InitMsg, PairingsResponse,
+    ParentCollectionsResponse, ParentNft, PayoutTableResponse, QueryBreedingsResponse, QueryMsg,
+    QueryUserBreedingsResponse, TreasuryResponse,
+};
+use my_contract::proto;
+use my_contract::state::{PriceTier, PricingMode};
+
+const OWNER: &str = "owner";
+const BREEDER: &str = "breeder";
+const DENOM: &str = "uusd";
+
+type Deps = OwnedDeps<MockStorage, MockApi, ParentQuerier>;
+
+// Answers every cw721 owner query with the breeder, so the breed in setup finds its parents
+struct ParentQuerier {
+    base: MockQuerier,
+}
+
+impl Querier for ParentQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        match from_slice(bin_request).unwrap() {
+            QueryRequest::<Empty>::Wasm(WasmQuery::Smart { .. }) => SystemResult::Ok(ContractResult::Ok(
+                to_binary(&OwnerOfResponse {
+                    owner: BREEDER.to_string(),
+                    approvals: vec![],
+                })
+                .unwrap(),
+            )),
+            _ => self.base.raw_query(bin_request),
+        }
+    }
+}
+
+// Instantiates the contract with a stepped pricing mode and starts one breed
+fn setup() -> Deps {
+    let mut deps = OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier: ParentQuerier {
+            base: MockQuerier::new(&[]),
+        },
+    };
+
+    let msg = InitMsg {
+        breed_count_limit: 10,
+        breed_duration: 3_600,
+        breed_price_amount: Uint128::from(1_000u128),
+        breed_price_denom: DENOM.to_string(),
+        breed_price_cw20: None,
+        pricing: Some(PricingMode::Stepped {
+            tiers: vec![PriceTier {
+                min_minted: 5,
+                amount: Uint128::from(2_000u128),
+            }],
+        }),
+        max_batch_size: None,
+        child_token_id_scheme: None,
+        child_base_uri: None,
+        child_uri_template: None,
+        royalty: None,
+        child_collection: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+
+    for collection in ["cats", "dogs"].iter() {
+        let msg = HandleMsg::AddParentCollection {
+            addr: collection.to_string(),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+    }
+    let msg = HandleMsg::SetPairing {
+        collection1: "cats".to_string(),
+        collection2: "dogs".to_string(),
+        breed_price_amount: None,
+        breed_duration: None,
+        child_contract_addr: Some("children".to_string()),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+
+    let msg = HandleMsg::Breed {
+        parent1: ParentNft {
+            collection: "cats".to_string(),
+            token_id: "cat1".to_string(),
+        },
+        parent2: ParentNft {
+            collection: "dogs".to_string(),
+            token_id: "dog1".to_string(),
+        },
+        proof: None,
+        auto_claim_tip: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info(BREEDER, &coins(1_000, DENOM)), msg).unwrap();
+
+    deps
+}
+
+// Queries both encodings, converts the JSON answer and compares it with the decoded protobuf one
+fn assert_same_data<J, P>(deps: &Deps, msg: QueryMsg) -> P
+where
+    J: DeserializeOwned,
+    P: Message + Default + From<J> + PartialEq + Debug,
+{
+    let json: J = from_binary(&query(deps.as_ref(), mock_env(), msg.clone()).unwrap()).unwrap();
+    let encoded = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Protobuf {
+            query: Box::new(msg),
+        },
+    )
+    .unwrap();
+
+    let decoded = P::decode(encoded.as_slice()).unwrap();
+    assert_eq!(decoded, P::from(json));
+
+    // Decoding and encoding again gives back the exact bytes
+    let mut reencoded = vec![];
+    decoded.encode(&mut reencoded).unwrap();
+    assert_eq!(reencoded, encoded.as_slice());
+
+    decoded
+}
+
+#[test]
+fn config_and_pricing() {
+    let deps = setup();
+    let config: proto::ConfigResponse = assert_same_data::<ConfigResponse, _>(&deps, QueryMsg::Config {});
+    assert_eq!(config.owner, OWNER);
+    assert_eq!(config.breed_price_amount, "1000");
+    assert_eq!(config.breed_price_cw20, None);
+    match config.pricing.unwrap().mode.unwrap() {
+        proto::pricing_mode::Mode::Stepped(stepped) => {
+            assert_eq!(stepped.tiers[0].min_minted, 5);
+            assert_eq!(stepped.tiers[0].amount, "2000");
+        }
+        mode => panic!("unexpected pricing mode {:?}", mode),
+    }
+
+    assert_same_data::<CurrentPriceResponse, proto::CurrentPriceResponse>(
+        &deps,
+        QueryMsg::CurrentPrice {
+            collection1: "cats".to_string(),
+            collection2: "dogs".to_string(),
+        },
+    );
+}
+
+#[test]
+fn breed_queries() {
+    let deps = setup();
+    let info: proto::BreedInfoResponse =
+        assert_same_data::<BreedInfoResponse, _>(&deps, QueryMsg::BreedInfo { breed_id: 1 });
+    assert_eq!(info.nft_owner, BREEDER);
+    assert_eq!(info.child_token_id, None);
+    assert!(!info.withdrawn);
+
+    let breedings: proto::QueryBreedingsResponse = assert_same_data::<QueryBreedingsResponse, _>(
+        &deps,
+        QueryMsg::QueryBreedings {
+            count: 10,
+            from: 0,
+            sort: "ascending".to_string(),
+        },
+    );
+    assert_eq!(breedings.breed_info, vec![info]);
+
+    let user_breedings: proto::QueryBreedingsResponse = assert_same_data::<QueryUserBreedingsResponse, _>(
+        &deps,
+        QueryMsg::QueryUserBreedings {
+            count: 10,
+            from: 0,
+            sort: "descending".to_string(),
+            user: BREEDER.to_string(),
+        },
+    );
+    assert_eq!(user_breedings, breedings);
+}
+
+#[test]
+fn count_queries() {
+    let deps = setup();
+    let queries = vec![
+        (QueryMsg::BreedRequestsCount {}, 1),
+        (QueryMsg::BreedFinishedCount {}, 0),
+        (QueryMsg::QueryBreedingsLength {}, 1),
+        (
+            QueryMsg::QueryUserBreedingsLength {
+                user: BREEDER.to_string(),
+            },
+            1,
+        ),
+        (
+            QueryMsg::BreededCount {
+                parent_nft_contract_addr: "cats".to_string(),
+                parent_nft_token_id: "cat1".to_string(),
+            },
+            0,
+        ),
+    ];
+    for (msg, count) in queries {
+        let response: proto::CountResponse = assert_same_data::<u32, _>(&deps, msg);
+        assert_eq!(response.count, count);
+    }
+
+    // The breed started in setup shows from the next block on
+    let height = mock_env().block.height + 1;
+    let total: proto::BreedStatsResponse =
+        assert_same_data::<BreedStatsResponse, _>(&deps, QueryMsg::TotalStatsAtHeight { height });
+    assert_eq!((total.breeds, total.children), (1, 0));
+    let breeder: proto::BreedStatsResponse = assert_same_data::<BreedStatsResponse, _>(
+        &deps,
+        QueryMsg::BreedStatsAtHeight {
+            address: BREEDER.to_string(),
+            height,
+        },
+    );
+    assert_eq!(breeder.breeds, 1);
+}
+
+#[test]
+fn treasury_and_registry_queries() {
+    let deps = setup();
+    let treasury: proto::TreasuryResponse = assert_same_data::<TreasuryResponse, _>(&deps, QueryMsg::Treasury {});
+    assert_eq!(treasury.entries[0].escrowed, "1000");
+    assert_eq!(treasury.entries[0].tips, "0");
+
+    // The only breed has not finished and did not opt into auto-claim
+    let claimable: proto::ClaimableBreedsResponse = assert_same_data::<ClaimableBreedsResponse, _>(
+        &deps,
+        QueryMsg::ClaimableBreeds { start_after: None, limit: None },
+    );
+    assert!(claimable.breeds.is_empty());
+    assert_eq!(
+        treasury.entries[0].denom.clone().unwrap().denom,
+        Some(proto::fee_denom::Denom::Native(DENOM.to_string()))
+    );
+
+    assert_same_data::<PayoutTableResponse, proto::PayoutTableResponse>(&deps, QueryMsg::PayoutTable {});
+    assert_same_data::<ParentCollectionsResponse, proto::ParentCollectionsResponse>(&deps, QueryMsg::ParentCollections {});
+    assert_same_data::<HooksResponse, proto::HooksResponse>(&deps, QueryMsg::Hooks {});
+    let pairings: proto::PairingsResponse = assert_same_data::<PairingsResponse, _>(&deps, QueryMsg::Pairings {});
+    assert_eq!(pairings.pairings[0].child_contract_addr, Some("children".to_string()));
+
+    let status: proto::AllowlistStatusResponse = assert_same_data::<AllowlistStatusResponse, _>(
+        &deps,
+        QueryMsg::AllowlistStatus {
+            address: BREEDER.to_string(),
+            proof: vec![],
+        },
+    );
+    assert_eq!(status.round_id, None);
+}
+
+#[test]
+fn nested_protobuf_query() {
+    let deps = setup();
+    let msg = QueryMsg::Protobuf {
+        query: Box::new(QueryMsg::Protobuf {
+            query: Box::new(QueryMsg::Config {}),
+        }),
+    };
+    let err = query(deps.as_ref(), mock_env(), msg).unwrap_err();
+    assert_eq!(err, ContractError::NestedProtobufQuery {});
+}
@@ -0,0 +1,284 @@
+// submessages.rs
+//
+// Covers the flows cw-multi-test 0.6 cannot run because it drops submessages, the breeding hooks
+// and the child collection instantiated through a reply, by calling the entry points on mocks.
+
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{
+    coins, from_binary, from_slice, to_binary, Binary, ContractResult, CosmosMsg, Empty, OwnedDeps,
+    Querier, QuerierResult, QueryRequest, Reply, ReplyOn, Response, SubcallResponse, SystemResult,
+    Uint128, WasmMsg, WasmQuery,
+};
+use cw721::OwnerOfResponse;
+use cw721_base::msg::InstantiateMsg as NftInstantiateMsg;
+use prost::Message;
+
+use my_contract::contract::{execute, instantiate, query, reply, MsgInstantiateContractResponse};
+use my_contract::error::ContractError;
+use my_contract::msg::{
+    BreedingHookExecuteMsg, BreedingHookMsg, ChildCollectionMsg, ConfigResponse, HandleMsg, InitMsg,
+    ParentNft, QueryMsg,
+};
+use my_contract::events;
+use my_contract::state::HookEvent;
+
+const OWNER: &str = "owner";
+const BREEDER: &str = "breeder";
+const RECORDER: &str = "recorder";
+const DENOM: &str = "uusd";
+const BREED_PRICE: u128 = 1_000;
+const BREED_DURATION: u64 = 3_600;
+
+type Deps = OwnedDeps<MockStorage, MockApi, ParentQuerier>;
+
+// Answers every cw721 owner query with the breeder, so breeds find their parents
+struct ParentQuerier {
+    base: MockQuerier,
+}
+
+impl Querier for ParentQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        match from_slice(bin_request).unwrap() {
+            QueryRequest::<Empty>::Wasm(WasmQuery::Smart { .. }) => SystemResult::Ok(ContractResult::Ok(
+                to_binary(&OwnerOfResponse {
+                    owner: BREEDER.to_string(),
+                    approvals: vec![],
+                })
+                .unwrap(),
+            )),
+            _ => self.base.raw_query(bin_request),
+        }
+    }
+}
+
+fn mock_deps() -> Deps {
+    OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier: ParentQuerier {
+            base: MockQuerier::new(&[]),
+        },
+    }
+}
+
+fn init_msg(child_collection: Option<ChildCollectionMsg>) -> InitMsg {
+    InitMsg {
+        breed_count_limit: 10,
+        breed_duration: BREED_DURATION,
+        breed_price_amount: Uint128::from(BREED_PRICE),
+        breed_price_denom: DENOM.to_string(),
+        breed_price_cw20: None,
+        pricing: None,
+        max_batch_size: None,
+        child_token_id_scheme: None,
+        child_base_uri: None,
+        child_uri_template: None,
+        royalty: None,
+        child_collection,
+    }
+}
+
+// Instantiates the contract and pairs two parent collections minting into "children"
+fn setup() -> Deps {
+    let mut deps = mock_deps();
+    instantiate(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), init_msg(None)).unwrap();
+    for collection in ["cats", "dogs"].iter() {
+        let msg = HandleMsg::AddParentCollection {
+            addr: collection.to_string(),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+    }
+    let msg = HandleMsg::SetPairing {
+        collection1: "cats".to_string(),
+        collection2: "dogs".to_string(),
+        breed_price_amount: None,
+        breed_duration: None,
+        child_contract_addr: Some("children".to_string()),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+    deps
+}
+
+fn breed(deps: &mut Deps, token1: &str, token2: &str) -> Response {
+    let msg = HandleMsg::Breed {
+        parent1: ParentNft {
+            collection: "cats".to_string(),
+            token_id: token1.to_string(),
+        },
+        parent2: ParentNft {
+            collection: "dogs".to_string(),
+            token_id: token2.to_string(),
+        },
+        proof: None,
+        auto_claim_tip: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info(BREEDER, &coins(BREED_PRICE, DENOM)), msg).unwrap()
+}
+
+// Decodes the hook messages a response sends to `hook`, every hook call only replies on error
+fn hook_msgs(res: &Response, hook: &str) -> Vec<BreedingHookMsg> {
+    res.submessages
+        .iter()
+        .filter_map(|sub| {
+            assert_eq!(sub.reply_on, ReplyOn::Error);
+            assert!(sub.gas_limit.is_some());
+            match &sub.msg {
+                CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, msg, .. }) if contract_addr == hook => {
+                    let BreedingHookExecuteMsg::BreedingHook(hook_msg) = from_binary(msg).unwrap();
+                    Some(hook_msg)
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn breeding_hooks() {
+    let mut deps = setup();
+
+    let msg = HandleMsg::AddHook {
+        addr: RECORDER.to_string(),
+        events: vec![],
+    };
+    let err = execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap_err();
+    assert_eq!(err, ContractError::InvalidHook { reason: "no events".to_string() });
+
+    let all_events = vec![
+        HookEvent::BreedStarted,
+        HookEvent::BreedCancelled,
+        HookEvent::BreedWithdrawn,
+        HookEvent::ChildMinted,
+    ];
+    for hook in [RECORDER, "children"].iter() {
+        let msg = HandleMsg::AddHook {
+            addr: hook.to_string(),
+            events: all_events.clone(),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+    }
+
+    let res = breed(&mut deps, "cat1", "dog1");
+    assert_eq!(res.submessages.len(), 2);
+    assert_eq!(
+        hook_msgs(&res, RECORDER),
+        vec![BreedingHookMsg::BreedStarted {
+            breed_id: 1,
+            owner: BREEDER.to_string(),
+            parent1: ParentNft {
+                collection: "cats".to_string(),
+                token_id: "cat1".to_string(),
+            },
+            parent2: ParentNft {
+                collection: "dogs".to_string(),
+                token_id: "dog1".to_string(),
+            },
+            end_time: mock_env().block.time.seconds() + BREED_DURATION,
+        }]
+    );
+
+    // The child collection cannot handle hook messages, its failure is only reported
+    let hook_reply_id = res.submessages[1].id;
+    let failure = Reply {
+        id: hook_reply_id,
+        result: ContractResult::Err("unknown variant `breeding_hook`".to_string()),
+    };
+    let res = reply(deps.as_mut(), mock_env(), failure).unwrap();
+    assert_eq!(res.attributes[0].value, events::ACTION_HOOK_FAILED);
+    assert!(res.messages.is_empty());
+    let success = Reply {
+        id: hook_reply_id,
+        result: ContractResult::Ok(SubcallResponse { events: vec![], data: None }),
+    };
+    let err = reply(deps.as_mut(), mock_env(), success).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidHookReply {
+            reason: "hook replies are only expected on error".to_string(),
+        }
+    );
+
+    breed(&mut deps, "cat2", "dog2");
+    let msg = HandleMsg::CancelBreed { breed_id: 2 };
+    let res = execute(deps.as_mut(), mock_env(), mock_info(BREEDER, &[]), msg).unwrap();
+    let breeder = BREEDER.to_string();
+    assert_eq!(
+        hook_msgs(&res, RECORDER),
+        vec![BreedingHookMsg::BreedCancelled { breed_id: 2, owner: breeder.clone() }]
+    );
+
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(BREED_DURATION);
+    let msg = HandleMsg::Withdraw { breed_id: 1 };
+    let res = execute(deps.as_mut(), env, mock_info(BREEDER, &[]), msg).unwrap();
+    assert_eq!(
+        hook_msgs(&res, RECORDER),
+        vec![
+            BreedingHookMsg::BreedWithdrawn { breed_id: 1, owner: breeder.clone() },
+            BreedingHookMsg::ChildMinted {
+                breed_id: Some(1),
+                child_contract_addr: "children".to_string(),
+                token_id: "1".to_string(),
+                owner: breeder,
+            },
+        ]
+    );
+
+    let msg = HandleMsg::RemoveHook {
+        addr: RECORDER.to_string(),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg.clone()).unwrap();
+    let err = execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap_err();
+    assert_eq!(err, ContractError::not_found("Hook"));
+    let res = breed(&mut deps, "cat3", "dog3");
+    assert!(hook_msgs(&res, RECORDER).is_empty());
+}
+
+#[test]
+fn child_collection_reply() {
+    let mut deps = mock_deps();
+    let collection = ChildCollectionMsg {
+        code_id: 7,
+        name: "Children".to_string(),
+        symbol: "CHILD".to_string(),
+        label: "children".to_string(),
+    };
+    let res = instantiate(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), init_msg(Some(collection))).unwrap();
+    assert_eq!(res.submessages.len(), 1);
+    let sub = &res.submessages[0];
+    assert_eq!(sub.reply_on, ReplyOn::Success);
+    match &sub.msg {
+        CosmosMsg::Wasm(WasmMsg::Instantiate { code_id, msg, label, .. }) => {
+            assert_eq!(*code_id, 7);
+            assert_eq!(label, "children");
+            let msg: NftInstantiateMsg = from_binary(msg).unwrap();
+            assert_eq!(msg.minter, mock_env().contract.address.to_string());
+        }
+        msg => panic!("unexpected message {:?}", msg),
+    }
+
+    // The chain answers with the address of the new collection
+    let mut data = vec![];
+    MsgInstantiateContractResponse {
+        contract_address: "children".to_string(),
+        data: vec![],
+    }
+    .encode(&mut data)
+    .unwrap();
+    let instantiated = Reply {
+        id: sub.id,
+        result: ContractResult::Ok(SubcallResponse {
+            events: vec![],
+            data: Some(Binary::from(data)),
+        }),
+    };
+    reply(deps.as_mut(), mock_env(), instantiated.clone()).unwrap();
+    let config: ConfigResponse = from_binary(
+        &query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(config.child_contract_addr, Some("children".to_string()));
+
+    // Only one reply is expected per instantiation
+    assert!(reply(deps.as_mut(), mock_env(), instantiated).is_err());
+}